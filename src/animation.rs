@@ -0,0 +1,317 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// 动画帧之间的间隔（约 60 FPS）
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimationKind {
+    /// 立即显示或隐藏
+    None,
+    #[default]
+    Fade,
+    /// 淡入的同时由小变大
+    Pop,
+    /// 淡入的同时由下向上滑入
+    Slide,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// 将线性进度 `t`（0..=1）映射为缓动后的进度
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnimationSetting {
+    pub kind: AnimationKind,
+    /// 动画时长（毫秒），为 0 时等同于 `None`
+    pub duration_ms: u64,
+    pub easing: Easing,
+}
+
+impl Default for AnimationSetting {
+    fn default() -> Self {
+        Self {
+            kind: AnimationKind::default(),
+            duration_ms: 150,
+            easing: Easing::default(),
+        }
+    }
+}
+
+/// 一帧的变换：先以窗口中心缩放，再纵向平移，最后乘以透明度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub alpha: f32,
+    pub scale: f32,
+    /// 纵向偏移，按窗口高度的比例计算，正值向下
+    pub offset_y: f32,
+}
+
+impl Frame {
+    pub const IDENTITY: Frame = Frame {
+        alpha: 1.0,
+        scale: 1.0,
+        offset_y: 0.0,
+    };
+
+    /// `visibility` 为 0 时完全隐藏，为 1 时即为原始画面
+    pub fn new(kind: AnimationKind, visibility: f32) -> Self {
+        let v = visibility.clamp(0.0, 1.0);
+        match kind {
+            AnimationKind::None => Frame {
+                alpha: if v > 0.0 { 1.0 } else { 0.0 },
+                ..Self::IDENTITY
+            },
+            AnimationKind::Fade => Frame {
+                alpha: v,
+                ..Self::IDENTITY
+            },
+            AnimationKind::Pop => Frame {
+                alpha: v,
+                scale: 0.6 + 0.4 * v,
+                offset_y: 0.0,
+            },
+            AnimationKind::Slide => Frame {
+                alpha: v,
+                scale: 1.0,
+                offset_y: 0.25 * (1.0 - v),
+            },
+        }
+    }
+}
+
+/// 一次显示或隐藏动画，可见度从 `from` 过渡到 `to`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    kind: AnimationKind,
+    easing: Easing,
+    duration: Duration,
+    start: Instant,
+    from: f32,
+    to: f32,
+}
+
+impl Animation {
+    /// 从当前可见度 `from` 开始过渡，动画中途反向时画面不会跳变
+    pub fn new(setting: &AnimationSetting, from: f32, to: f32, now: Instant) -> Self {
+        let duration = match setting.kind {
+            AnimationKind::None => Duration::ZERO,
+            _ => Duration::from_millis(setting.duration_ms),
+        };
+        // 只剩部分路程时按比例缩短时长，保持速度一致
+        let distance = (to - from).abs();
+        let duration = if distance < 1.0 {
+            duration.mul_f32(distance)
+        } else {
+            duration
+        };
+
+        Self {
+            kind: setting.kind,
+            easing: setting.easing,
+            duration,
+            start: now,
+            from,
+            to,
+        }
+    }
+
+    pub fn visibility(&self, now: Instant) -> f32 {
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            now.saturating_duration_since(self.start).as_secs_f32() / self.duration.as_secs_f32()
+        };
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    pub fn frame(&self, now: Instant) -> Frame {
+        Frame::new(self.kind, self.visibility(now))
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
+
+/// 将预乘 ARGB 画面 `source` 按 `frame` 变换后写入同样大小的 `target`
+pub fn apply_frame(source: &[u32], target: &mut [u32], width: u32, height: u32, frame: Frame) {
+    if frame.alpha <= 0.0 || frame.scale <= 0.0 {
+        target.fill(0);
+        return;
+    }
+
+    if frame == Frame::IDENTITY {
+        target.copy_from_slice(source);
+        return;
+    }
+
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+    let offset_y = frame.offset_y * height as f32;
+    let alpha = frame.alpha.min(1.0);
+
+    for y in 0..height {
+        for x in 0..width {
+            // 反向映射：目标像素中心对应的源像素（最近邻采样）
+            let source_x = (x as f32 + 0.5 - center_x) / frame.scale + center_x;
+            let source_y = (y as f32 + 0.5 - center_y - offset_y) / frame.scale + center_y;
+
+            let pixel = if source_x < 0.0
+                || source_y < 0.0
+                || source_x >= width as f32
+                || source_y >= height as f32
+            {
+                0
+            } else {
+                source[(source_y as u32 * width + source_x as u32) as usize]
+            };
+
+            target[(y * width + x) as usize] = fade_pixel(pixel, alpha);
+        }
+    }
+}
+
+/// 预乘像素的四个通道同时乘以 `alpha`
+fn fade_pixel(pixel: u32, alpha: f32) -> u32 {
+    if alpha >= 1.0 {
+        return pixel;
+    }
+
+    (0..4).fold(0, |faded, i| {
+        let channel = ((pixel >> (i * 8)) & 0xFF) as f32;
+        faded | (((channel * alpha).round() as u32) << (i * 8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_starts_at_zero_and_ends_at_one() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+            assert!(easing.apply(0.25) <= easing.apply(0.75));
+        }
+
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn animation_interpolates_visibility_over_time() {
+        let setting = AnimationSetting {
+            kind: AnimationKind::Fade,
+            duration_ms: 100,
+            easing: Easing::Linear,
+        };
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let fade_in = Animation::new(&setting, 0.0, 1.0, start);
+        assert_eq!(fade_in.visibility(start), 0.0);
+        assert!((fade_in.visibility(at(50)) - 0.5).abs() < 1e-3);
+        assert!(!fade_in.is_finished(at(99)));
+        assert!(fade_in.is_finished(at(100)));
+        assert_eq!(fade_in.visibility(at(500)), 1.0);
+
+        // 淡入一半时反向：从 0.5 开始，只需一半时长
+        let fade_out = Animation::new(&setting, 0.5, 0.0, start);
+        assert_eq!(fade_out.visibility(start), 0.5);
+        assert!(!fade_out.is_finished(at(40)));
+        assert!(fade_out.is_finished(at(51)));
+        assert_eq!(fade_out.frame(at(51)).alpha, 0.0);
+
+        let none = Animation::new(
+            &AnimationSetting {
+                kind: AnimationKind::None,
+                ..setting
+            },
+            0.0,
+            1.0,
+            start,
+        );
+        assert!(none.is_finished(start));
+        assert_eq!(none.frame(start), Frame::IDENTITY);
+    }
+
+    #[test]
+    fn frames_end_at_identity() {
+        for kind in [
+            AnimationKind::None,
+            AnimationKind::Fade,
+            AnimationKind::Pop,
+            AnimationKind::Slide,
+        ] {
+            assert_eq!(Frame::new(kind, 1.0), Frame::IDENTITY);
+            assert_eq!(Frame::new(kind, 0.0).alpha, 0.0);
+        }
+
+        assert!(Frame::new(AnimationKind::Pop, 0.5).scale < 1.0);
+        assert!(Frame::new(AnimationKind::Slide, 0.5).offset_y > 0.0);
+    }
+
+    #[test]
+    fn frame_fades_scales_and_moves_pixels() {
+        let white = 0xFFFFFFFF;
+        let source = vec![white; 4 * 4];
+        let mut target = vec![0u32; 4 * 4];
+
+        let half = Frame {
+            alpha: 0.5,
+            ..Frame::IDENTITY
+        };
+        apply_frame(&source, &mut target, 4, 4, half);
+        assert!(target.iter().all(|p| *p == 0x80808080));
+
+        let shrunk = Frame {
+            scale: 0.5,
+            ..Frame::IDENTITY
+        };
+        apply_frame(&source, &mut target, 4, 4, shrunk);
+        #[rustfmt::skip]
+        assert_eq!(target, vec![
+            0, 0, 0, 0,
+            0, white, white, 0,
+            0, white, white, 0,
+            0, 0, 0, 0,
+        ]);
+
+        let moved = Frame {
+            offset_y: 0.5,
+            ..Frame::IDENTITY
+        };
+        apply_frame(&source, &mut target, 4, 4, moved);
+        assert!(target[..8].iter().all(|p| *p == 0));
+        assert!(target[8..].iter().all(|p| *p == white));
+    }
+}
@@ -0,0 +1,683 @@
+use std::{
+    ffi::OsString,
+    process::Command,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        mpsc,
+    },
+    time::Instant,
+};
+
+use crate::{
+    indicator_window::{IndicatorWindow, WindowLayout, is_excluded_from_capture},
+    tray::{
+        create_tray,
+        menu::{MenuManager, about, handler::MenuHandler, item::create_menu},
+    },
+    uiaccess::prepare_uiaccess_token,
+};
+use capsglow::{
+    animation::{Animation, AnimationKind, FRAME_INTERVAL, Frame},
+    config::{CONFIG_PATH, Config, EXE_NAME, EXE_PATH},
+    icon::{CustomIcon, indicator_window_size},
+    key::{HOOK_DEBOUNCE, KeyDetection, KeyStates, KeyWatcher, LockKey, watch_lock_keys},
+    language::LOC,
+    monitor::DISPLAY_SETTLE_DELAY,
+    osd::{Indicator, IndicatorVisibility},
+    platform::{
+        DisplayChange, InstanceLock, KeyHook, MonitorProvider, Point, WindowsPlatform,
+        show_warning_message, watch_display_changes, watch_drag_end, watch_theme_changes,
+    },
+    render::IndicatorRenderer,
+};
+
+use anyhow::{Context, Result};
+use log::error;
+use tray_icon::{TrayIcon, menu::MenuEvent};
+use winit::{
+    application::ApplicationHandler,
+    event::{ElementState, MouseButton, StartCause, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
+    platform::windows::WindowExtWindows,
+    window::WindowId,
+};
+
+pub fn run() -> Result<()> {
+    let _single_instance = WindowsPlatform.acquire(&EXE_NAME)?;
+
+    let _uiaccess_token =
+        prepare_uiaccess_token().inspect(|_| println!("Successful acquisition of Uiaccess"));
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let event_loop = EventLoop::<UserEvent>::with_user_event().build()?;
+
+    let proxy = event_loop.create_proxy();
+    MenuEvent::set_event_handler(Some(move |event| {
+        proxy
+            .send_event(UserEvent::MenuEvent(event))
+            .expect("Failed to send MenuEvent");
+    }));
+
+    let proxy = event_loop.create_proxy();
+    let mut app = App::new(proxy);
+    event_loop.run_app(&mut app)?;
+
+    Ok(())
+}
+
+struct App {
+    /// 正在进行的显示或隐藏动画
+    animation: Option<Animation>,
+    config: Arc<Config>,
+    exit_threads: Arc<AtomicBool>,
+    event_loop_proxy: EventLoopProxy<UserEvent>,
+    /// 跟随鼠标时，下一次更新窗口位置的时间
+    follow_cursor_at: Option<Instant>,
+    /// 没有指示器需要显示时，隐藏窗口的时间
+    hide_window_at: Option<Instant>,
+    /// 跟随指示器区域背景时，下一次重新取样背景的时间
+    resample_theme_at: Option<Instant>,
+    /// 显示器布局变化后，等待系统完成重新排列再次定位窗口的时间
+    relayout_at: Option<Instant>,
+    key_states: Arc<AtomicU8>,
+    menu_manager: Mutex<MenuManager>,
+    /// 拖动放置期间第一个窗口始终显示，并且可以用鼠标拖动
+    placing: bool,
+    renderer: IndicatorRenderer,
+    show_indicator: bool,
+    tray: Mutex<TrayIcon>,
+    visibility: IndicatorVisibility,
+    /// 指示器窗口，在所有显示器上显示时每个显示器一个
+    ///
+    /// 第一个窗口创建后一直保留，显示器与工作区变化的消息由它接收
+    windows: Vec<IndicatorWindow>,
+}
+
+impl App {
+    fn new(event_loop_proxy: EventLoopProxy<UserEvent>) -> Self {
+        let config = Config::open().expect("Failed to open config");
+
+        // 发布版本不输出日志，配置文件的错误需要直接告知用户
+        if let Some(e) = config.load_error() {
+            let message = format!("{}\n{}\n\n{e}", LOC.invalid_config, CONFIG_PATH.display());
+            std::thread::spawn(move || show_warning_message("CapsGlow", &message));
+        }
+
+        let (tray, menu_manager) = create_tray(&config).expect("Failed to create tray");
+
+        let renderer = IndicatorRenderer::new(
+            CustomIcon::find_custom_icons(),
+            &config.get_font_setting(),
+            WindowsPlatform,
+        );
+
+        Self {
+            animation: None,
+            config: Arc::new(config),
+            exit_threads: Arc::new(AtomicBool::new(false)),
+            event_loop_proxy,
+            follow_cursor_at: None,
+            hide_window_at: None,
+            relayout_at: None,
+            resample_theme_at: None,
+            key_states: Arc::new(AtomicU8::new(0)),
+            menu_manager: Mutex::new(menu_manager),
+            placing: false,
+            renderer,
+            show_indicator: false,
+            tray: Mutex::new(tray),
+            visibility: IndicatorVisibility::default(),
+            windows: Vec::new(),
+        }
+    }
+
+    fn create_window(&mut self, event_loop: &ActiveEventLoop) -> Result<()> {
+        if !self.windows.is_empty() {
+            return Ok(());
+        }
+
+        let layout = self
+            .window_layouts()?
+            .into_iter()
+            .next()
+            .context("No monitor to show the indicator")?;
+
+        let window = IndicatorWindow::new(event_loop, layout)?;
+
+        // 任务栏移动、工作区或显示器变化时重新定位窗口
+        if let Some(hwnd) = window.hwnd() {
+            let proxy = self.event_loop_proxy.clone();
+            if let Err(e) = watch_display_changes(hwnd.0 as isize, move |change| {
+                let _ = proxy.send_event(UserEvent::DisplayChanged(change));
+            }) {
+                log::error!("{e}");
+            }
+
+            let proxy = self.event_loop_proxy.clone();
+            if let Err(e) = watch_drag_end(hwnd.0 as isize, move || {
+                let _ = proxy.send_event(UserEvent::FinishPlacing);
+            }) {
+                log::error!("{e}");
+            }
+
+            // 切换深浅色模式或强调色后立即以新的颜色重绘
+            let proxy = self.event_loop_proxy.clone();
+            if let Err(e) = watch_theme_changes(hwnd.0 as isize, move || {
+                let _ = proxy.send_event(UserEvent::RedrawRequested);
+            }) {
+                log::error!("{e}");
+            }
+        }
+
+        self.windows.push(window);
+        self.layout_windows(event_loop);
+
+        let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+
+        Ok(())
+    }
+
+    fn exit(&mut self) {
+        self.exit_threads.store(true, Ordering::Relaxed);
+        // 卸载键盘钩子，钩子线程退出后监听线程的接收端断开，不再阻塞
+        WindowsPlatform.stop_key_hook();
+    }
+
+    /// 每个指示器窗口的物理尺寸与位置，尺寸按窗口所在显示器的 DPI 计算
+    fn window_layouts(&self) -> Result<Vec<WindowLayout>> {
+        let size = self.config.get_indicator_size();
+
+        if self.config.is_all_monitors() {
+            let layouts = WindowsPlatform
+                .monitors()
+                .into_iter()
+                .map(|monitor| {
+                    let corner = Point::new(monitor.area.monitor.left, monitor.area.monitor.top);
+                    let scale = WindowsPlatform.monitor_scale_factor(corner);
+                    let (width, height) =
+                        indicator_window_size(self.renderer.custom_icons(), size, scale);
+                    let position =
+                        self.config
+                            .get_mirror_phy_position(monitor.area, scale, width, height);
+                    WindowLayout {
+                        size: (width, height),
+                        position,
+                        scale,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if !layouts.is_empty() {
+                return Ok(layouts);
+            }
+        }
+
+        let scale = self.config.get_target_scale_factor(&WindowsPlatform)?;
+        let (width, height) = indicator_window_size(self.renderer.custom_icons(), size, scale);
+        let position = self
+            .config
+            .get_window_phy_position(&WindowsPlatform, width, height)?;
+
+        Ok(vec![WindowLayout {
+            size: (width, height),
+            position,
+            scale,
+        }])
+    }
+
+    /// 按当前设置移动指示器窗口，显示器增减时创建或关闭窗口，移动到 DPI 不同的显示器时调整窗口大小
+    fn layout_windows(&mut self, event_loop: &ActiveEventLoop) {
+        let layouts = match self.window_layouts() {
+            Ok(layouts) => layouts,
+            Err(e) => return error!("Failed to get window physical position: {e}"),
+        };
+
+        let mut needs_redraw = layouts.len() != self.windows.len();
+        self.windows.truncate(layouts.len().max(1));
+
+        for (index, layout) in layouts.into_iter().enumerate() {
+            match self.windows.get_mut(index) {
+                Some(window) => match window.apply_layout(layout) {
+                    Ok(resized) => needs_redraw |= resized,
+                    Err(e) => error!("Failed to resize window: {e}"),
+                },
+                None => match IndicatorWindow::new(event_loop, layout) {
+                    Ok(window) => self.windows.push(window),
+                    Err(e) => error!("Failed to create window: {e}"),
+                },
+            }
+        }
+
+        if needs_redraw {
+            let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+        }
+    }
+
+    /// 指示器显示且跟随鼠标时，安排下一次更新窗口位置
+    fn schedule_cursor_follow(&mut self) {
+        self.follow_cursor_at = self
+            .config
+            .get_cursor_follow_interval()
+            .filter(|_| self.show_indicator)
+            .map(|interval| Instant::now() + interval);
+    }
+
+    /// 指示器显示且跟随指示器区域背景时，安排下一次重新取样背景
+    ///
+    /// 指示器窗口未被排除在屏幕截取之外时，显示期间取样会截取到窗口自身，因此不重新取样
+    fn schedule_theme_resample(&mut self) {
+        self.resample_theme_at = self
+            .config
+            .get_theme_resample_interval()
+            .filter(|_| self.show_indicator && is_excluded_from_capture())
+            .map(|interval| Instant::now() + interval);
+    }
+
+    /// 重新取样各窗口背后的背景，颜色变化时重绘
+    fn resample_theme(&mut self) {
+        let indicator_theme = *self.config.indicator_theme.lock().unwrap();
+        let adaptive = self.config.get_adaptive_theme_setting();
+
+        let changed = self.windows.iter().any(|window| {
+            let colors = indicator_theme.get_colors(
+                &WindowsPlatform,
+                window.outer_rect().unwrap_or_default(),
+                &adaptive,
+                window.colors().map(|colors| colors.theme),
+            );
+            window.colors() != Some(colors)
+        });
+
+        if changed {
+            let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+        }
+    }
+
+    /// 按当前动画进度将各窗口的画面绘制到窗口，动画结束后清除动画
+    fn present_frame(&mut self) {
+        let now = Instant::now();
+        let frame = match &self.animation {
+            Some(animation) => animation.frame(now),
+            None => Frame::new(
+                AnimationKind::None,
+                if self.show_indicator { 1.0 } else { 0.0 },
+            ),
+        };
+        if self
+            .animation
+            .as_ref()
+            .is_some_and(|animation| animation.is_finished(now))
+        {
+            self.animation = None;
+        }
+
+        self.windows
+            .iter_mut()
+            .for_each(|window| window.present(frame));
+    }
+
+    fn listen_lock_keys(&self) {
+        let exit_threads = Arc::clone(&self.exit_threads);
+        let key_detection = self.config.get_key_detection();
+        let key_states = Arc::clone(&self.key_states);
+        let proxy = self.event_loop_proxy.clone();
+
+        std::thread::spawn(move || {
+            let mut watcher = KeyWatcher::new(WindowsPlatform, HOOK_DEBOUNCE);
+            let publish = |states: KeyStates| {
+                key_states.store(states.bits(), Ordering::Relaxed);
+                let _ = proxy.send_event(UserEvent::RedrawRequested);
+            };
+
+            publish(watcher.last());
+
+            let (sender, receiver) = mpsc::channel();
+            if key_detection == KeyDetection::Hook {
+                std::thread::spawn(move || {
+                    if let Err(e) = WindowsPlatform.run_key_hook(sender) {
+                        error!("{e}, fall back to polling");
+                    }
+                });
+            } else {
+                drop(sender);
+            }
+
+            watch_lock_keys(&mut watcher, receiver, &exit_threads, publish);
+        });
+    }
+}
+
+#[derive(Debug)]
+pub enum UserEvent {
+    DisplayChanged(DisplayChange),
+    FinishPlacing,
+    HideWindow,
+    Exit,
+    MenuEvent(MenuEvent),
+    MoveWindow,
+    RefreshTrayMenu,
+    Restart,
+    ShowAboutDialog,
+    StartPlacing,
+    RedrawRequested,
+}
+
+impl ApplicationHandler<UserEvent> for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        self.create_window(event_loop)
+            .expect("Failed to create window");
+        self.listen_lock_keys();
+    }
+
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            if self
+                .hide_window_at
+                .is_some_and(|hide_window_at| Instant::now() >= hide_window_at)
+            {
+                self.hide_window_at = None;
+                let _ = self.event_loop_proxy.send_event(UserEvent::HideWindow);
+            }
+
+            if self
+                .relayout_at
+                .is_some_and(|relayout_at| Instant::now() >= relayout_at)
+            {
+                self.relayout_at = None;
+                let _ = self.event_loop_proxy.send_event(UserEvent::RefreshTrayMenu);
+                let _ = self.event_loop_proxy.send_event(UserEvent::MoveWindow);
+            }
+
+            if !self.placing
+                && self
+                    .follow_cursor_at
+                    .is_some_and(|follow_cursor_at| Instant::now() >= follow_cursor_at)
+            {
+                self.layout_windows(event_loop);
+                self.schedule_cursor_follow();
+            }
+
+            if self
+                .resample_theme_at
+                .is_some_and(|resample_theme_at| Instant::now() >= resample_theme_at)
+            {
+                self.resample_theme();
+                self.schedule_theme_resample();
+            }
+
+            if self
+                .visibility
+                .deadline()
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+            }
+
+            self.present_frame();
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // 动画进行中时按帧间隔唤醒事件循环，OSD、隐藏窗口、跟随鼠标、重新定位或重新取样背景到期时也需要唤醒
+        let next_frame = self
+            .animation
+            .as_ref()
+            .map(|_| Instant::now() + FRAME_INTERVAL);
+        let wake_at = next_frame
+            .into_iter()
+            .chain(self.visibility.deadline())
+            .chain(self.hide_window_at)
+            .chain(self.follow_cursor_at)
+            .chain(self.relayout_at)
+            .chain(self.resample_theme_at)
+            .min();
+
+        event_loop.set_control_flow(wake_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil));
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
+        match event {
+            // 拖动放置：按住左键拖动窗口，松开后由 `watch_drag_end` 通知保存位置
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if self.placing => {
+                if let Some(window) = self.windows.first().filter(|window| window.id() == id) {
+                    let _ = window.window().drag_window();
+                }
+            }
+            WindowEvent::CloseRequested => {
+                self.exit();
+                event_loop.exit();
+            }
+            // 显示器缩放比例变化或窗口被移到其他显示器，按新的 DPI 调整窗口大小并重新绘制
+            WindowEvent::ScaleFactorChanged { .. } => {
+                let _ = self.event_loop_proxy.send_event(UserEvent::MoveWindow);
+            }
+            WindowEvent::RedrawRequested => {
+                // WARN: 发送 windows.request_redraw() 请求重绘，如果托盘菜单正在打开中，Windows 消息循环（Message Loop）被阻塞，会导致重绘失败
+            }
+            _ => {}
+        }
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
+        match event {
+            UserEvent::DisplayChanged(change) => {
+                self.layout_windows(event_loop);
+                self.schedule_cursor_follow();
+
+                // 显示器增减时更新托盘中的显示器列表，稍后按最终的布局再定位一次
+                if change == DisplayChange::Topology {
+                    let _ = self.event_loop_proxy.send_event(UserEvent::RefreshTrayMenu);
+                    self.relayout_at = Some(Instant::now() + DISPLAY_SETTLE_DELAY);
+                }
+            }
+            UserEvent::FinishPlacing => {
+                let Some(window) = self.windows.first().filter(|_| self.placing) else {
+                    return;
+                };
+
+                self.placing = false;
+                window.set_interactive(false);
+
+                if let Some(rect) = window.outer_rect() {
+                    match self.config.set_custom_position(&WindowsPlatform, rect) {
+                        Ok(()) => self.config.save(),
+                        Err(e) => error!("Failed to save the dragged position: {e}"),
+                    }
+                }
+
+                let _ = self.event_loop_proxy.send_event(UserEvent::RefreshTrayMenu);
+                let _ = self.event_loop_proxy.send_event(UserEvent::MoveWindow);
+                let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+            }
+            UserEvent::StartPlacing => {
+                let Some(window) = self.windows.first() else {
+                    return;
+                };
+
+                self.placing = true;
+                window.set_interactive(true);
+                let _ = self.event_loop_proxy.send_event(UserEvent::RedrawRequested);
+            }
+            UserEvent::HideWindow => {
+                self.windows
+                    .iter()
+                    .for_each(|window| window.window().set_visible(false));
+                log::info!("Window set invisible");
+            }
+            UserEvent::Exit => {
+                self.exit();
+                event_loop.exit();
+            }
+            UserEvent::MenuEvent(event) => {
+                let mut menu_manager = self.menu_manager.lock().unwrap();
+                menu_manager.handler(event.id(), |is_normal_menu, check_menu| {
+                    let menu_handlers = MenuHandler::new(
+                        event.id().clone(),
+                        is_normal_menu,
+                        check_menu,
+                        Arc::clone(&self.config),
+                        self.event_loop_proxy.clone(),
+                    );
+
+                    let _ = menu_handlers
+                        .run()
+                        .inspect_err(|e| error!("Failed to handle menu event: {e}"));
+                });
+            }
+            UserEvent::MoveWindow => {
+                self.layout_windows(event_loop);
+                self.schedule_cursor_follow();
+            }
+            UserEvent::RefreshTrayMenu => match create_menu(&self.config) {
+                Ok((tray_menu, menu_manager)) => {
+                    self.tray
+                        .lock()
+                        .unwrap()
+                        .set_menu(Some(Box::new(tray_menu)));
+                    *self.menu_manager.lock().unwrap() = menu_manager;
+                }
+                Err(e) => error!("Failed to refresh tray menu: {e}"),
+            },
+            UserEvent::RedrawRequested => {
+                if self.windows.is_empty() {
+                    self.create_window(event_loop)
+                        .expect("Failed to create window");
+                    return;
+                }
+
+                let mut active_indicators = self.visibility.update(
+                    &self.config.get_display_setting(),
+                    &self.config.watched_keys.lock().unwrap(),
+                    KeyStates::from_bits(self.key_states.load(Ordering::Relaxed)),
+                    Instant::now(),
+                );
+
+                // 拖动放置期间显示所有启用的锁定键，即使锁定键均未开启也能看到并拖动窗口
+                if self.placing {
+                    let watched_keys = self.config.watched_keys.lock().unwrap();
+                    active_indicators = LockKey::ALL
+                        .into_iter()
+                        .filter(|key| watched_keys.get(*key).enabled)
+                        .map(Indicator::Key)
+                        .collect();
+                }
+
+                let show_indicator = !active_indicators.is_empty();
+
+                // 跟随文字光标或鼠标时，每次显示都移动到光标当前所在位置
+                if show_indicator
+                    && !self.show_indicator
+                    && self.config.get_window_position().follows_input()
+                {
+                    self.layout_windows(event_loop);
+                }
+
+                for window in &self.windows {
+                    window.window().set_visible(true);
+                }
+
+                if show_indicator {
+                    self.renderer
+                        .set_style(&self.config.indicator_style.lock().unwrap());
+                    self.renderer.set_palette(&self.config.get_palette());
+                    let indicator_theme = *self.config.indicator_theme.lock().unwrap();
+                    let adaptive = self.config.get_adaptive_theme_setting();
+
+                    for window in &mut self.windows {
+                        window.window().set_skip_taskbar(true);
+                        window.window().set_minimized(false);
+
+                        let (window_width, window_height) = window.size();
+
+                        // 显示期间取样会截取到未被排除的窗口自身，沿用上次取样的颜色
+                        let colors = match window.colors() {
+                            Some(colors)
+                                if self.show_indicator
+                                    && indicator_theme.samples_background()
+                                    && !is_excluded_from_capture() =>
+                            {
+                                colors
+                            }
+                            previous => indicator_theme.get_colors(
+                                &WindowsPlatform,
+                                window.outer_rect().unwrap_or_default(),
+                                &adaptive,
+                                previous.map(|colors| colors.theme),
+                            ),
+                        };
+
+                        let mut content = vec![0u32; (window_width * window_height) as usize];
+                        self.renderer.render(
+                            &mut content,
+                            window_width,
+                            window_height,
+                            &active_indicators,
+                            colors,
+                            &self.config.watched_keys.lock().unwrap(),
+                        );
+                        window.set_content(content, colors);
+                    }
+                }
+
+                // 显示状态变化时从当前可见度开始过渡，动画中途反向不会跳变
+                if show_indicator != self.show_indicator {
+                    let now = Instant::now();
+                    let from = match &self.animation {
+                        Some(animation) => animation.visibility(now),
+                        None if self.show_indicator => 1.0,
+                        None => 0.0,
+                    };
+                    let to = if show_indicator { 1.0 } else { 0.0 };
+
+                    self.show_indicator = show_indicator;
+                    self.animation = Some(Animation::new(
+                        &self.config.get_animation_setting(),
+                        from,
+                        to,
+                        now,
+                    ));
+                    self.schedule_cursor_follow();
+                    self.schedule_theme_resample();
+                }
+
+                // 显示期间切换为跟随指示器区域背景时开始重新取样
+                if self.show_indicator && self.resample_theme_at.is_none() {
+                    self.schedule_theme_resample();
+                }
+
+                // 没有指示器时延迟隐藏窗口，再次显示指示器时取消
+                self.hide_window_at = if show_indicator {
+                    None
+                } else {
+                    self.hide_window_at.or_else(|| {
+                        Some(Instant::now() + self.config.get_display_setting().hide_window_delay())
+                    })
+                };
+
+                self.present_frame();
+            }
+            UserEvent::Restart => {
+                let args_os: Vec<OsString> = std::env::args_os().collect();
+
+                if let Err(e) = Command::new(&*EXE_PATH)
+                    .args(args_os.iter().skip(1))
+                    .spawn()
+                {
+                    error!("Failed to restart app: {e}");
+                }
+
+                let _ = self.event_loop_proxy.send_event(UserEvent::Exit);
+            }
+            UserEvent::ShowAboutDialog => {
+                let hwnd = self.tray.lock().unwrap().window_handle();
+                about::show_about_dialog(hwnd as isize);
+            }
+        }
+    }
+}
@@ -1,14 +1,22 @@
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use winit::dpi::PhysicalPosition;
 
-use crate::monitor::MonitorSelector;
-use crate::theme::IndicatorTheme;
-use crate::window::{WindowPosition, WindowSetting};
+use crate::animation::AnimationSetting;
+use crate::font::FontSetting;
+use crate::key::{KeyDetection, KeySetting, LockKey, WatchedKeys};
+use crate::monitor::{MonitorId, MonitorSelector};
+use crate::osd::{DisplayMode, DisplaySetting};
+use crate::palette::{Palette, PalettePreset};
+use crate::platform::{MonitorArea, MonitorProvider, Point, Rect};
+use crate::style::{FontWeight, IndicatorStyle, StyleKind};
+use crate::theme::{AdaptiveThemeSetting, IndicatorTheme};
+use crate::window::{IndicatorSize, WindowPosition, WindowSetting};
 
+/// 默认的指示器边长（逻辑像素），即 `IndicatorSize::MEDIUM`
 pub const WINDOW_LOGICAL_SIZE: f64 = 200.0;
 
 pub static EXE_PATH: LazyLock<PathBuf> =
@@ -36,6 +44,25 @@ pub static CONFIG_PATH: LazyLock<PathBuf> =
 pub struct Config {
     pub window_setting: Mutex<WindowSetting>,
     pub indicator_theme: Mutex<IndicatorTheme>,
+    #[serde(default)]
+    pub adaptive_theme: Mutex<AdaptiveThemeSetting>,
+    #[serde(default)]
+    pub watched_keys: Mutex<WatchedKeys>,
+    #[serde(default)]
+    pub key_detection: Mutex<KeyDetection>,
+    #[serde(default)]
+    pub font: Mutex<FontSetting>,
+    #[serde(default)]
+    pub indicator_style: Mutex<IndicatorStyle>,
+    #[serde(default)]
+    pub animation: Mutex<AnimationSetting>,
+    #[serde(default)]
+    pub display: Mutex<DisplaySetting>,
+    #[serde(default)]
+    pub palette: Mutex<Palette>,
+    /// 配置文件有错误而使用了默认配置时的错误信息，此时不写回，以免覆盖用户的配置文件
+    #[serde(skip)]
+    load_error: Option<String>,
 }
 
 impl Default for Config {
@@ -43,33 +70,76 @@ impl Default for Config {
         Self {
             window_setting: Mutex::new(WindowSetting::default()),
             indicator_theme: Mutex::new(IndicatorTheme::default()),
+            adaptive_theme: Mutex::new(AdaptiveThemeSetting::default()),
+            watched_keys: Mutex::new(WatchedKeys::default()),
+            key_detection: Mutex::new(KeyDetection::default()),
+            font: Mutex::new(FontSetting::default()),
+            indicator_style: Mutex::new(IndicatorStyle::default()),
+            animation: Mutex::new(AnimationSetting::default()),
+            display: Mutex::new(DisplaySetting::default()),
+            palette: Mutex::new(Palette::default()),
+            load_error: None,
         }
     }
 }
 
 impl Config {
     pub fn open() -> Result<Self> {
+        Config::open_at(&CONFIG_PATH)
+    }
+
+    fn open_at(path: &Path) -> Result<Self> {
         let default_config = Config::default();
 
-        Config::read().or_else(|e| {
+        // 配置文件存在但有错误（如无效的颜色）时保留原文件，错误信息中包含出错的行与键
+        if path.is_file() {
+            return Ok(Config::read(path).unwrap_or_else(|e| {
+                log::error!("Invalid config file, use the default config instead: {e}");
+                Config {
+                    load_error: Some(e.to_string()),
+                    ..default_config
+                }
+            }));
+        }
+
+        Config::read(path).or_else(|e| {
             log::warn!("Failed to read the config file: {e}\nNow creat a new config file");
             let toml_str = toml::to_string_pretty(&default_config)?;
-            std::fs::write(&*CONFIG_PATH, toml_str)?;
+            std::fs::write(path, toml_str)?;
             Ok(default_config)
         })
     }
 
-    fn read() -> Result<Self> {
-        let content = std::fs::read_to_string(&*CONFIG_PATH)?;
+    /// 读取配置文件，失败时使用默认配置（不会创建或覆盖配置文件）
+    pub fn read_or_default() -> Self {
+        Config::read(&CONFIG_PATH).unwrap_or_default()
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
         let toml_config: Config = toml::from_str(&content)?;
         Ok(toml_config)
     }
 
+    /// 配置文件的错误信息（包含出错的行与键），配置文件有效时为 `None`
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    /// 保存配置；配置文件有错误时不保存，本次运行中的修改只在内存中生效
     pub fn save(&self) {
+        self.save_to(&CONFIG_PATH);
+    }
+
+    fn save_to(&self, path: &Path) {
+        if self.load_error.is_some() {
+            log::warn!("The config file is invalid, skip saving to keep it");
+            return;
+        }
+
         let toml_str = toml::to_string_pretty(self)
             .expect("Failed to serialize ConfigToml structure as a String of TOML.");
-        std::fs::write(&*CONFIG_PATH, toml_str)
-            .expect("Failed to write TOML String to CapsGlow.toml");
+        std::fs::write(path, toml_str).expect("Failed to write TOML String to CapsGlow.toml");
     }
 }
 
@@ -81,6 +151,13 @@ impl Config {
         )
     }
 
+    pub fn is_all_monitors(&self) -> bool {
+        matches!(
+            self.window_setting.lock().unwrap().monitor_selector,
+            MonitorSelector::AllMonitors
+        )
+    }
+
     pub fn is_mouse_monitor(&self) -> bool {
         matches!(
             self.window_setting.lock().unwrap().monitor_selector,
@@ -102,19 +179,118 @@ impl Config {
         )
     }
 
+    pub fn is_indicator_contrast_theme(&self) -> bool {
+        matches!(
+            *self.indicator_theme.lock().unwrap(),
+            IndicatorTheme::Contrast
+        )
+    }
+
+    pub fn is_indicator_accent_theme(&self) -> bool {
+        matches!(
+            *self.indicator_theme.lock().unwrap(),
+            IndicatorTheme::Accent
+        )
+    }
+
+    pub fn is_key_enabled(&self, key: LockKey) -> bool {
+        self.watched_keys.lock().unwrap().get(key).enabled
+    }
+
+    pub fn get_key_setting(&self, key: LockKey) -> KeySetting {
+        self.watched_keys.lock().unwrap().get(key).clone()
+    }
+
+    pub fn get_key_detection(&self) -> KeyDetection {
+        *self.key_detection.lock().unwrap()
+    }
+
+    pub fn get_font_setting(&self) -> FontSetting {
+        self.font.lock().unwrap().clone()
+    }
+
+    pub fn get_indicator_style(&self) -> IndicatorStyle {
+        self.indicator_style.lock().unwrap().clone()
+    }
+
+    pub fn get_animation_setting(&self) -> AnimationSetting {
+        self.animation.lock().unwrap().clone()
+    }
+
+    pub fn get_display_setting(&self) -> DisplaySetting {
+        self.display.lock().unwrap().clone()
+    }
+
+    pub fn get_monitor_selector(&self) -> MonitorSelector {
+        self.window_setting.lock().unwrap().monitor_selector.clone()
+    }
+
     pub fn get_window_position(&self) -> WindowPosition {
         self.window_setting.lock().unwrap().position.clone()
     }
 
+    pub fn get_palette(&self) -> Palette {
+        self.palette.lock().unwrap().clone()
+    }
+
+    pub fn get_adaptive_theme_setting(&self) -> AdaptiveThemeSetting {
+        self.adaptive_theme.lock().unwrap().clone()
+    }
+
+    /// 跟随指示器区域背景（含对比度模式）时，显示期间重新取样背景的间隔
+    pub fn get_theme_resample_interval(&self) -> Option<Duration> {
+        if !self.indicator_theme.lock().unwrap().samples_background() {
+            return None;
+        }
+
+        self.adaptive_theme.lock().unwrap().interval()
+    }
+
+    pub fn get_indicator_size(&self) -> IndicatorSize {
+        self.window_setting.lock().unwrap().size
+    }
+
+    /// 跟随鼠标时返回更新窗口位置的间隔，在所有显示器上显示时不跟随
+    pub fn get_cursor_follow_interval(&self) -> Option<Duration> {
+        let window_setting = self.window_setting.lock().unwrap();
+        (window_setting.position == WindowPosition::Cursor
+            && window_setting.monitor_selector != MonitorSelector::AllMonitors)
+            .then(|| window_setting.follow.interval())
+    }
+
+    pub fn get_target_scale_factor(&self, monitors: &impl MonitorProvider) -> Result<f64> {
+        self.window_setting
+            .lock()
+            .unwrap()
+            .get_target_scale_factor(monitors)
+    }
+
+    pub fn get_mirror_phy_position(
+        &self,
+        area: MonitorArea,
+        scale: f64,
+        window_phy_width: u32,
+        window_phy_height: u32,
+    ) -> Point {
+        self.window_setting.lock().unwrap().get_mirror_phy_position(
+            area,
+            scale,
+            window_phy_width,
+            window_phy_height,
+        )
+    }
+
     pub fn get_window_phy_position(
         &self,
+        monitors: &impl MonitorProvider,
         window_phy_width: u32,
         window_phy_height: u32,
-    ) -> Result<PhysicalPosition<i32>> {
-        self.window_setting
-            .lock()
-            .unwrap()
-            .get_phy_position(window_phy_width, window_phy_height)
+    ) -> Result<Point> {
+        self.window_setting.lock().unwrap().get_phy_position(
+            monitors,
+            window_phy_width,
+            window_phy_height,
+        )
     }
 }
 
@@ -127,6 +303,14 @@ impl Config {
         self.window_setting.lock().unwrap().monitor_selector = MonitorSelector::MouseMonitor;
     }
 
+    pub fn set_all_monitors(&self) {
+        self.window_setting.lock().unwrap().monitor_selector = MonitorSelector::AllMonitors;
+    }
+
+    pub fn set_specific_monitor(&self, id: MonitorId) {
+        self.window_setting.lock().unwrap().monitor_selector = MonitorSelector::Specific(id);
+    }
+
     pub fn set_indicator_system_theme(&self) {
         *self.indicator_theme.lock().unwrap() = IndicatorTheme::System;
     }
@@ -135,11 +319,137 @@ impl Config {
         *self.indicator_theme.lock().unwrap() = IndicatorTheme::IndicatorArea;
     }
 
+    pub fn set_indicator_contrast_theme(&self) {
+        *self.indicator_theme.lock().unwrap() = IndicatorTheme::Contrast;
+    }
+
+    pub fn set_indicator_accent_theme(&self) {
+        *self.indicator_theme.lock().unwrap() = IndicatorTheme::Accent;
+    }
+
+    pub fn set_palette_preset(&self, preset: PalettePreset) {
+        self.palette.lock().unwrap().preset = preset;
+    }
+
+    pub fn set_key_enabled(&self, key: LockKey, enabled: bool) {
+        self.watched_keys.lock().unwrap().get_mut(key).enabled = enabled;
+    }
+
+    pub fn set_style_kind(&self, kind: StyleKind) {
+        self.indicator_style.lock().unwrap().kind = kind;
+    }
+
+    pub fn set_font_weight(&self, weight: FontWeight) {
+        self.indicator_style.lock().unwrap().weight = weight;
+    }
+
+    pub fn set_background_plate(&self, enabled: bool) {
+        self.indicator_style.lock().unwrap().background_plate = enabled;
+    }
+
+    pub fn set_display_mode(&self, mode: DisplayMode) {
+        self.display.lock().unwrap().mode = mode;
+    }
+
+    pub fn set_osd_duration(&self, duration_ms: u64) {
+        self.display.lock().unwrap().osd_duration_ms = duration_ms;
+    }
+
+    pub fn set_osd_show_off(&self, show_off: bool) {
+        self.display.lock().unwrap().osd_show_off = show_off;
+    }
+
     pub fn set_window_position(&self, position: WindowPosition) {
-        let mut window_setting = self.window_setting.lock().unwrap();
-        *window_setting = WindowSetting {
-            position,
-            monitor_selector: window_setting.monitor_selector.clone(),
-        };
+        self.window_setting.lock().unwrap().position = position;
+    }
+
+    pub fn set_indicator_size(&self, size: IndicatorSize) {
+        self.window_setting.lock().unwrap().size = size;
+    }
+
+    pub fn set_custom_position(
+        &self,
+        monitors: &impl MonitorProvider,
+        window_rect: Rect,
+    ) -> Result<()> {
+        self.window_setting
+            .lock()
+            .unwrap()
+            .set_custom_position(monitors, window_rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_config_files_get_defaults_for_new_sections() {
+        let config: Config = toml::from_str(
+            r#"
+            indicator_theme = "System"
+
+            [window_setting]
+            position = "TopRight"
+            monitor_selector = "PrimaryMonitor"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.is_indicator_system_theme());
+        assert!(config.is_primary_monitor());
+        assert_eq!(config.get_window_position(), WindowPosition::TopRight);
+        assert!(config.is_key_enabled(LockKey::Caps));
+        assert!(!config.is_key_enabled(LockKey::Num));
+        assert_eq!(config.get_key_detection(), KeyDetection::Hook);
+        assert_eq!(config.get_display_setting(), DisplaySetting::default());
+        assert_eq!(config.get_indicator_size(), IndicatorSize::MEDIUM);
+        assert_eq!(
+            config.get_adaptive_theme_setting(),
+            AdaptiveThemeSetting::default()
+        );
+        assert_eq!(config.get_theme_resample_interval(), None);
+    }
+
+    #[test]
+    fn invalid_config_files_are_not_overwritten() {
+        let path = std::env::temp_dir().join(format!("CapsGlow-{}.toml", std::process::id()));
+        let content = "[palette.dark]\nfont = \"white\"\n";
+        std::fs::write(&path, content).unwrap();
+
+        let config = Config::open_at(&path).unwrap();
+        assert_eq!(config.get_palette(), Palette::default());
+        let error = config.load_error().unwrap();
+        assert!(error.contains("font = \"white\""), "{error}");
+
+        config.set_window_position(WindowPosition::Bottom);
+        config.save_to(&path);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved, content);
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let config = Config::default();
+        config.set_key_enabled(LockKey::Scroll, true);
+        config.set_window_position(WindowPosition::Bottom);
+        let monitor_id = MonitorId(r"\\?\DISPLAY#DEL40F3#5&1a2b3c4d&0&UID4353".to_owned());
+        config.set_specific_monitor(monitor_id.clone());
+        config.set_indicator_size(IndicatorSize(160.0));
+        config.set_palette_preset(PalettePreset::Soft);
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let config: Config = toml::from_str(&toml_str).unwrap();
+
+        assert!(config.is_key_enabled(LockKey::Scroll));
+        assert_eq!(config.get_window_position(), WindowPosition::Bottom);
+        assert_eq!(
+            config.get_monitor_selector(),
+            MonitorSelector::Specific(monitor_id)
+        );
+        assert_eq!(config.get_indicator_size(), IndicatorSize(160.0));
+        assert_eq!(config.get_palette().preset, PalettePreset::Soft);
     }
 }
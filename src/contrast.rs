@@ -0,0 +1,96 @@
+//! 按 WCAG 对比度根据背景颜色选择指示器的前景色
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+
+/// sRGB 颜色的相对亮度（WCAG 2.x 定义），范围 0–1
+pub fn relative_luminance([r, g, b]: [u8; 3]) -> f32 {
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// 两种颜色的对比度，范围 1–21，与顺序无关
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// 白色与黑色中与 `color` 对比度更高的一方
+pub fn contrasting_extreme(color: [u8; 3]) -> [u8; 3] {
+    if contrast_ratio(color, WHITE) >= contrast_ratio(color, BLACK) {
+        WHITE
+    } else {
+        BLACK
+    }
+}
+
+/// 在 `background` 上达到 `target` 对比度、且与背景色调最接近的前景色
+///
+/// 从背景色向对比度更高的白色或黑色渐变，取第一个满足目标的颜色；无法达到目标时使用白色或黑色
+pub fn best_foreground(background: [u8; 3], target: f32) -> [u8; 3] {
+    let extreme = contrasting_extreme(background);
+    if contrast_ratio(extreme, background) <= target {
+        return extreme;
+    }
+
+    let mix = |t: f32| {
+        std::array::from_fn(|i| {
+            let (from, to) = (background[i] as f32, extreme[i] as f32);
+            (from + (to - from) * t).round() as u8
+        })
+    };
+
+    // 向白色（黑色）渐变时亮度单调增加（减少），对比度随之单调增加，二分查找满足目标的最小比例
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    for _ in 0..16 {
+        let mid = (low + high) / 2.0;
+        if contrast_ratio(mix(mid), background) >= target {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    mix(high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contrast_ratio_matches_wcag() {
+        assert!((contrast_ratio(WHITE, BLACK) - 21.0).abs() < 0.01);
+        assert_eq!(contrast_ratio([120, 60, 200], [120, 60, 200]), 1.0);
+        // #767676 是白色背景上刚好达到 4.5:1 的灰色
+        assert!((contrast_ratio([0x76, 0x76, 0x76], WHITE) - 4.54).abs() < 0.01);
+    }
+
+    #[test]
+    fn foreground_reaches_the_target_with_the_background_hue() {
+        let navy = [20, 30, 90];
+        let foreground = best_foreground(navy, 4.5);
+        let ratio = contrast_ratio(foreground, navy);
+        assert!((4.5..4.7).contains(&ratio), "{ratio}");
+        // 在深色背景上变亮，并保留偏蓝的色调
+        assert!(foreground[2] > foreground[0]);
+        assert!(relative_luminance(foreground) > relative_luminance(navy));
+
+        let paper = [250, 245, 230];
+        let foreground = best_foreground(paper, 7.0);
+        assert!(contrast_ratio(foreground, paper) >= 7.0);
+        assert!(relative_luminance(foreground) < relative_luminance(paper));
+
+        // 中灰色背景上无法达到 21:1，使用对比度更高的黑色
+        assert_eq!(best_foreground([128, 128, 128], 21.0), BLACK);
+        assert_eq!(contrasting_extreme(WHITE), BLACK);
+    }
+}
@@ -0,0 +1,155 @@
+//! 内置指示器使用的字体
+//!
+//! 按 `font`、`fallback_fonts` 的顺序查找能绘制指定文字的字体，
+//! 都不可用时使用编译进程序的内置字体（DejaVu Sans 子集），保证指示器始终能绘制出来。
+
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{Font, FontVec};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{key::LockKey, platform::FontLocator};
+
+pub const BUNDLED_FONT_DATA: &[u8] = include_bytes!("../assets/fallback.ttf");
+
+/// 字体来源：字体文件路径，或系统已安装的字体名称
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FontSource {
+    File(PathBuf),
+    Family(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontSetting {
+    pub font: FontSource,
+    /// `font` 不可用或缺少字形时依次尝试的字体
+    pub fallback_fonts: Vec<FontSource>,
+}
+
+impl Default for FontSetting {
+    fn default() -> Self {
+        Self {
+            font: FontSource::Family("Segoe UI Emoji".to_owned()),
+            fallback_fonts: vec![FontSource::Family("Segoe UI Symbol".to_owned())],
+        }
+    }
+}
+
+/// 字体回退链
+///
+/// 字体文件只在首次绘制时读取，读取或解析失败的字体会被跳过
+pub struct FontChain {
+    paths: Vec<PathBuf>,
+    fonts: Option<Vec<FontVec>>,
+    bundled: FontVec,
+}
+
+impl FontChain {
+    pub fn new(setting: &FontSetting, locator: &(impl FontLocator + ?Sized)) -> Self {
+        let paths = std::iter::once(&setting.font)
+            .chain(&setting.fallback_fonts)
+            .filter_map(|source| match source {
+                FontSource::File(path) => Some(path.clone()),
+                FontSource::Family(family) => {
+                    let path = locator.find_font_family(family);
+                    if path.is_none() {
+                        log::warn!("Font family '{family}' is not installed");
+                    }
+                    path
+                }
+            })
+            .collect();
+
+        Self {
+            paths,
+            fonts: None,
+            bundled: FontVec::try_from_vec(BUNDLED_FONT_DATA.to_vec())
+                .expect("The bundled font should be valid"),
+        }
+    }
+
+    /// 返回能绘制 `glyph` 中所有字符的字体与实际要绘制的文字
+    ///
+    /// 没有字体能完整绘制 `glyph` 时，使用内置字体绘制 `key` 的回退字符
+    pub fn resolve(&mut self, key: LockKey, glyph: &str) -> (&FontVec, String) {
+        let fonts = self.fonts.get_or_insert_with(|| {
+            self.paths
+                .iter()
+                .filter_map(|path| load_font(path).inspect_err(|e| log::warn!("{e:#}")).ok())
+                .collect()
+        });
+
+        if let Some(font) = fonts.iter().find(|font| has_glyphs(*font, glyph)) {
+            return (font, glyph.to_owned());
+        }
+
+        if has_glyphs(&self.bundled, glyph) {
+            return (&self.bundled, glyph.to_owned());
+        }
+
+        log::warn!("No font can draw '{glyph}', use the bundled font instead");
+        (&self.bundled, key.fallback_glyph().to_string())
+    }
+
+    /// 内置字体
+    pub fn bundled(&self) -> &FontVec {
+        &self.bundled
+    }
+}
+
+fn load_font(path: &Path) -> Result<FontVec> {
+    let font_data = std::fs::read(path)
+        .with_context(|| format!("Failed to read font file: {}", path.display()))?;
+    FontVec::try_from_vec(font_data)
+        .map_err(|e| anyhow!("Failed to parse font {} - {e}", path.display()))
+}
+
+/// 字体是否包含 `text` 中所有可见字符的字形
+fn has_glyphs(font: &impl Font, text: &str) -> bool {
+    !text.trim().is_empty()
+        && text
+            .chars()
+            .filter(|c| !c.is_whitespace() && !is_variation_selector(*c))
+            .all(|c| font.glyph_id(c).0 != 0)
+}
+
+/// 变体选择符（如 emoji 的 U+FE0F）不需要字形
+pub fn is_variation_selector(c: char) -> bool {
+    matches!(c, '\u{FE00}'..='\u{FE0F}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::fake::FakePlatform;
+
+    #[test]
+    fn missing_fonts_fall_back_to_the_bundled_font() {
+        let setting = FontSetting {
+            font: FontSource::File(PathBuf::from("does-not-exist.ttf")),
+            fallback_fonts: vec![FontSource::Family("Segoe UI Emoji".to_owned())],
+        };
+        let mut fonts = FontChain::new(&setting, &FakePlatform::default());
+
+        let (_, text) = fonts.resolve(LockKey::Caps, "\u{1F512}");
+        assert_eq!(text, "\u{21EA}");
+
+        let (_, text) = fonts.resolve(LockKey::Num, "NUM");
+        assert_eq!(text, "NUM");
+    }
+
+    #[test]
+    fn bundled_font_covers_every_fallback_glyph() {
+        let fonts = FontChain::new(&FontSetting::default(), &());
+
+        for key in LockKey::ALL {
+            assert!(has_glyphs(
+                fonts.bundled(),
+                &key.fallback_glyph().to_string()
+            ));
+        }
+        assert!(has_glyphs(fonts.bundled(), "Caps 123"));
+    }
+}
@@ -1,24 +1,20 @@
-use std::{path::PathBuf, sync::LazyLock};
+use std::{collections::HashMap, path::PathBuf};
 
-use ab_glyph::{Font, FontVec, Glyph, Point, PxScale};
+#[cfg(target_os = "windows")]
 use anyhow::{Context, Result, anyhow};
-use image::{ImageBuffer, ImageReader, Rgba};
+use image::{ImageBuffer, ImageReader, Rgba, imageops::FilterType};
 
-use crate::{config::EXE_PATH, theme::SystemTheme};
+use crate::{config::EXE_PATH, key::LockKey, theme::SystemTheme, window::IndicatorSize};
 
 pub const LOGO_DATA: &[u8] = include_bytes!("../assets/logo.ico");
 
-pub static INDICATOR_ICON_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| EXE_PATH.with_file_name("capslock.png"));
-
-pub static INDICATOR_ICON_DARK_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| EXE_PATH.with_file_name("capslock_dark.png"));
-
-pub static INDICATOR_ICON_LIGHT_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| EXE_PATH.with_file_name("capslock_light.png"));
+/// 自定义图标路径：`<key>.png`，或主题图标 `<key>_dark.png` + `<key>_light.png`
+fn custom_icon_path(key: LockKey, suffix: &str) -> PathBuf {
+    EXE_PATH.with_file_name(format!("{}{suffix}.png", key.icon_name()))
+}
 
 #[derive(Debug, Clone, PartialEq)]
-enum IconDate {
+enum IconData {
     Normal(ImageBuffer<Rgba<u8>, Vec<u8>>),
     Theme {
         light: ImageBuffer<Rgba<u8>, Vec<u8>>,
@@ -28,48 +24,61 @@ enum IconDate {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CustomIcon {
-    icon: IconDate,
+    icon: IconData,
     size: (u32, u32),
 }
 
 impl CustomIcon {
-    pub fn find_custom_icon() -> Option<Self> {
-        if INDICATOR_ICON_PATH.is_file() {
-            let icon_date = ImageReader::open(&*INDICATOR_ICON_PATH)
+    /// 查找所有锁定键的自定义图标，未提供图标的锁定键使用内置字符
+    pub fn find_custom_icons() -> HashMap<LockKey, Self> {
+        LockKey::ALL
+            .into_iter()
+            .filter_map(|key| Self::find_custom_icon(key).map(|icon| (key, icon)))
+            .inspect(|(key, icon)| log::info!("Custom icon size for {key:?}: {:?}", icon.size))
+            .collect()
+    }
+
+    pub fn find_custom_icon(key: LockKey) -> Option<Self> {
+        let icon_path = custom_icon_path(key, "");
+        let icon_dark_path = custom_icon_path(key, "_dark");
+        let icon_light_path = custom_icon_path(key, "_light");
+
+        if icon_path.is_file() {
+            let icon_data = ImageReader::open(&icon_path)
                 .ok()?
                 .decode()
                 .ok()?
                 .into_rgba8();
 
-            let (width, height) = icon_date.dimensions();
+            let (width, height) = icon_data.dimensions();
 
             Some(CustomIcon {
-                icon: IconDate::Normal(icon_date),
+                icon: IconData::Normal(icon_data),
                 size: (width, height),
             })
-        } else if INDICATOR_ICON_DARK_PATH.is_file() && INDICATOR_ICON_LIGHT_PATH.is_file() {
-            let icon_dark_date = ImageReader::open(&*INDICATOR_ICON_DARK_PATH)
+        } else if icon_dark_path.is_file() && icon_light_path.is_file() {
+            let icon_dark_data = ImageReader::open(&icon_dark_path)
                 .ok()?
                 .decode()
                 .ok()?
                 .into_rgba8();
 
-            let icon_light_date = ImageReader::open(&*INDICATOR_ICON_LIGHT_PATH)
+            let icon_light_data = ImageReader::open(&icon_light_path)
                 .ok()?
                 .decode()
                 .ok()?
                 .into_rgba8();
 
             // 确保深浅色图标大小一致
-            let (width, height) = icon_dark_date.dimensions();
-            if icon_light_date.dimensions() != (width, height) {
+            let (width, height) = icon_dark_data.dimensions();
+            if icon_light_data.dimensions() != (width, height) {
                 log::error!("Icon size mismatch between light and dark themes.");
                 None
             } else {
                 Some(CustomIcon {
-                    icon: IconDate::Theme {
-                        light: icon_light_date,
-                        dark: icon_dark_date,
+                    icon: IconData::Theme {
+                        light: icon_light_data,
+                        dark: icon_dark_data,
                     },
                     size: (width, height),
                 })
@@ -79,171 +88,66 @@ impl CustomIcon {
         }
     }
 
-    pub fn get_icon_date_and_size(
+    /// `theme` 主题下的原始图标，尺寸为 `get_size`
+    pub fn get_icon_data(&self, theme: SystemTheme) -> &ImageBuffer<Rgba<u8>, Vec<u8>> {
+        match &self.icon {
+            IconData::Normal(data) => data,
+            IconData::Theme { light, dark } => match theme {
+                SystemTheme::Light => light,
+                SystemTheme::Dark => dark,
+            },
+        }
+    }
+
+    /// 按 `width × height` 等比重新采样后的图标，尺寸相同时不重新采样
+    pub fn get_resized_icon_data_and_size(
         &self,
         theme: SystemTheme,
+        width: u32,
+        height: u32,
     ) -> (ImageBuffer<Rgba<u8>, Vec<u8>>, (u32, u32)) {
-        match &self.icon {
-            IconDate::Normal(data) => (data.clone(), self.size),
-            IconDate::Theme { light, dark } => match theme {
-                SystemTheme::Light => (light.clone(), self.size),
-                SystemTheme::Dark => (dark.clone(), self.size),
-            },
+        let icon_data = self.get_icon_data(theme);
+        let fitted = fit_size(self.size, width, height);
+        if fitted == self.size {
+            return (icon_data.clone(), self.size);
         }
+
+        let resized = image::imageops::resize(icon_data, fitted.0, fitted.1, FilterType::Lanczos3);
+        (resized, fitted)
     }
 
     pub fn get_size(&self) -> (u32, u32) {
-        log::info!("Custom icon size: {:?}", self.size);
         self.size
     }
 }
 
-pub fn render_font_to_sufface(
-    buffer: &mut softbuffer::Buffer<
-        '_,
-        std::rc::Rc<winit::window::Window>,
-        std::rc::Rc<winit::window::Window>,
-    >,
-    color: Rgba<u8>,
-    window_physical_width: u32,
-    window_physical_height: u32,
-) -> Result<()> {
-    let font_path = r"C:\WINDOWS\FONTS\SEGUIEMJ.TTF";
-    let font_data = std::fs::read(font_path)?;
-    let font = FontVec::try_from_vec(font_data).context("Failed to parse font")?;
-
-    let base_scale = PxScale::from(100.0); // 任意较大的基准值
-
-    let glyph_id = font.glyph_id('\u{1F512}');
-    let glyph = glyph_id.with_scale(base_scale);
-    let outlined = font.outline_glyph(glyph).unwrap();
-    let bounds = outlined.px_bounds();
-
-    let window_width = window_physical_width as f32;
-    let window_height = window_physical_height as f32;
-
-    let factor = f32::min(
-        window_width / bounds.width(),
-        window_height / bounds.height(),
+/// 将 `size` 等比缩放到恰好放入 `width × height`
+fn fit_size((icon_width, icon_height): (u32, u32), width: u32, height: u32) -> (u32, u32) {
+    let factor = f64::min(
+        width as f64 / icon_width.max(1) as f64,
+        height as f64 / icon_height.max(1) as f64,
     );
-
-    let final_scale = PxScale {
-        x: base_scale.x * factor,
-        y: base_scale.y * factor,
-    };
-
-    let glyph_for_bounds = glyph_id.with_scale(final_scale);
-    let outlined = font.outline_glyph(glyph_for_bounds).unwrap();
-    let final_bounds = outlined.px_bounds();
-
-    let position = Point {
-        x: (window_width - final_bounds.width()) / 2.0 - final_bounds.min.x,
-        y: (window_height - final_bounds.height()) / 2.0 - final_bounds.min.y,
-    };
-
-    let glyph = Glyph {
-        id: glyph_id,
-        scale: final_scale,
-        position,
-    };
-
-    let sr = color[0] as f32 / 255.0;
-    let sg = color[1] as f32 / 255.0;
-    let sb = color[2] as f32 / 255.0;
-    let sa = color[3] as f32 / 255.0;
-
-    let stride = u32::from(buffer.width());
-
-    if let Some(outlined) = font.outline_glyph(glyph) {
-        let bounds = outlined.px_bounds();
-        let start_x = bounds.min.x as i32;
-        let start_y = bounds.min.y as i32;
-
-        outlined.draw(|x, y, coverage| {
-            // 计算在 buffer 中的绝对坐标
-            // x, y 是相对于 bounds.min 的偏移
-            let screen_x = start_x + x as i32;
-            let screen_y = start_y + y as i32;
-
-            if screen_x < 0
-                || screen_x >= window_physical_width as i32
-                || screen_y < 0
-                || screen_y >= window_physical_height as i32
-            {
-                return;
-            }
-
-            let out_a = coverage * sa;
-            if out_a <= 0.0 {
-                return;
-            }
-
-            let r = (sr * out_a * 255.0) as u32;
-            let g = (sg * out_a * 255.0) as u32;
-            let b = (sb * out_a * 255.0) as u32;
-            let a = (out_a * 255.0) as u32;
-
-            let idx = (screen_y as u32 * stride + screen_x as u32) as usize;
-            buffer[idx] = (a << 24) | (r << 16) | (g << 8) | b;
-        });
-    }
-
-    Ok(())
+    (
+        ((icon_width as f64 * factor).round() as u32).max(1),
+        ((icon_height as f64 * factor).round() as u32).max(1),
+    )
 }
 
-pub fn render_icon_to_buffer(
-    buffer: &mut softbuffer::Buffer<
-        '_,
-        std::rc::Rc<winit::window::Window>,
-        std::rc::Rc<winit::window::Window>,
-    >,
-    icon_buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>,
-    icon_size: (u32, u32),
-    window_physical_width: u32,
-    window_physical_height: u32,
-) -> Result<()> {
-    let stride = u32::from(buffer.width());
-
-    // 计算居中位置
-    let start_x = (window_physical_width.saturating_sub(icon_size.0)) / 2;
-    let start_y = (window_physical_height.saturating_sub(icon_size.1)) / 2;
-
-    // 确保图标不会超出窗口边界
-    let render_width = icon_size
-        .0
-        .min(window_physical_width.saturating_sub(start_x));
-    let render_height = icon_size
-        .1
-        .min(window_physical_height.saturating_sub(start_y));
-
-    // 遍历需要渲染的每个像素
-    for y in 0..render_height {
-        for x in 0..render_width {
-            let pixel = icon_buffer.get_pixel(x, y).0;
-            let a = pixel[3] as u32;
-
-            // Alpha blending fix：预乘
-            let alpha_f = a as f32 / 255.0;
-            let r = (pixel[0] as f32 * alpha_f).round() as u32;
-            let g = (pixel[1] as f32 * alpha_f).round() as u32;
-            let b = (pixel[2] as f32 * alpha_f).round() as u32;
-
-            let dst_x = start_x + x;
-            let dst_y = start_y + y;
-
-            // 修正后的索引计算：y * 宽度 + x
-            let idx = (dst_y * stride + dst_x) as usize;
-
-            // 写入 buffer
-            if idx < buffer.len() {
-                buffer[idx] = (a << 24) | (r << 16) | (g << 8) | b;
-            }
-        }
-    }
-
-    Ok(())
+/// 指示器窗口的物理尺寸：边长为按缩放比例换算的 `size`，存在自定义图标时按最大的自定义图标的宽高比缩放
+pub fn indicator_window_size(
+    custom_icons: &HashMap<LockKey, CustomIcon>,
+    size: IndicatorSize,
+    scale: f64,
+) -> (u32, u32) {
+    let side = size.to_phy(scale);
+    custom_icons
+        .values()
+        .map(|i| i.get_size())
+        .reduce(|(w1, h1), (w2, h2)| (w1.max(w2), h1.max(h2)))
+        .map_or((side, side), |icon_size| fit_size(icon_size, side, side))
 }
 
+#[cfg(target_os = "windows")]
 pub fn load_icon_for_tray() -> Result<tray_icon::Icon> {
     let (icon_rgba, icon_width, icon_height) = {
         let image = image::load_from_memory(LOGO_DATA)
@@ -257,6 +161,7 @@ pub fn load_icon_for_tray() -> Result<tray_icon::Icon> {
         .with_context(|| "Failed to crate the logo")
 }
 
+#[cfg(target_os = "windows")]
 pub fn load_icon_for_window() -> Result<winit::window::Icon> {
     let (icon_rgba, icon_width, icon_height) = {
         let image = image::load_from_memory(LOGO_DATA)
@@ -269,3 +174,30 @@ pub fn load_icon_for_window() -> Result<winit::window::Icon> {
     winit::window::Icon::from_rgba(icon_rgba, icon_width, icon_height)
         .with_context(|| "Failed to crate the logo")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_icons_are_resampled_to_the_indicator_size() {
+        let icon = CustomIcon {
+            icon: IconData::Normal(ImageBuffer::from_pixel(64, 32, Rgba([255, 0, 0, 255]))),
+            size: (64, 32),
+        };
+        let custom_icons = HashMap::from([(LockKey::Caps, icon.clone())]);
+
+        assert_eq!(
+            indicator_window_size(&custom_icons, IndicatorSize::LARGE, 1.5),
+            (450, 225)
+        );
+        assert_eq!(
+            indicator_window_size(&HashMap::new(), IndicatorSize::SMALL, 1.0),
+            (120, 120)
+        );
+
+        let (resized, size) = icon.get_resized_icon_data_and_size(SystemTheme::Dark, 450, 225);
+        assert_eq!(size, (450, 225));
+        assert_eq!(resized.dimensions(), size);
+    }
+}
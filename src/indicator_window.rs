@@ -0,0 +1,228 @@
+use std::{num::NonZeroU32, rc::Rc};
+
+use capsglow::{
+    animation::{Frame, apply_frame},
+    icon::load_icon_for_window,
+    platform::{Point, Rect, supports_exclude_from_capture},
+    theme::IndicatorColors,
+};
+
+use anyhow::{Context, Result, anyhow};
+use softbuffer::Surface;
+use windows::Win32::Foundation::HWND;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event_loop::ActiveEventLoop,
+    platform::windows::{CornerPreference, WindowAttributesExtWindows, WindowExtWindows},
+    raw_window_handle::{HasWindowHandle, RawWindowHandle},
+    window::{Window, WindowId, WindowLevel},
+};
+
+/// 指示器窗口的物理尺寸、位置与所在显示器的缩放比例
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowLayout {
+    pub size: (u32, u32),
+    pub position: Point,
+    pub scale: f64,
+}
+
+/// 指示器窗口是否被排除在屏幕截取之外，此时显示期间也能取样窗口背后的背景
+///
+/// 调试版本不启用，便于录屏；Windows 10 2004 之前的系统不支持 `WDA_EXCLUDEFROMCAPTURE`，
+/// 启用内容保护只会让窗口在取样结果中显示为黑色，因此也不启用
+pub fn is_excluded_from_capture() -> bool {
+    !cfg!(debug_assertions) && supports_exclude_from_capture()
+}
+
+/// 一个指示器窗口及其绘制缓冲区
+///
+/// 通常只有一个窗口；在所有显示器上显示时，每个显示器各有一个窗口，尺寸按该显示器的 DPI 计算
+pub struct IndicatorWindow {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+    /// 窗口与缓冲区的物理尺寸
+    size: (u32, u32),
+    /// 窗口所在显示器的缩放比例
+    scale: f64,
+    /// 最近一次绘制的指示器画面（未应用动画），隐藏动画期间保留旧画面
+    content: Vec<u32>,
+    /// 最近一次绘制使用的颜色，跟随背景重新取样时据此判断是否需要重绘
+    colors: Option<IndicatorColors>,
+}
+
+impl IndicatorWindow {
+    pub fn new(event_loop: &ActiveEventLoop, layout: WindowLayout) -> Result<Self> {
+        let WindowLayout {
+            size: (width, height),
+            position,
+            scale,
+        } = layout;
+        let window_size = PhysicalSize::new(width, height);
+
+        let window_attributes = Window::default_attributes()
+            .with_visible(false)
+            .with_title("CapsGlow")
+            .with_corner_preference(CornerPreference::DoNotRound)
+            .with_skip_taskbar(!cfg!(debug_assertions)) // 隐藏任务栏图标
+            .with_undecorated_shadow(cfg!(debug_assertions)) // 隐藏窗口阴影
+            .with_content_protected(is_excluded_from_capture()) // 防止窗口被其他应用捕获，取样背景时也不会截取到自身
+            .with_window_level(WindowLevel::AlwaysOnTop) // 置顶
+            .with_inner_size(window_size)
+            .with_min_inner_size(window_size)
+            .with_max_inner_size(window_size)
+            .with_window_icon(load_icon_for_window().ok())
+            .with_position(PhysicalPosition::new(position.x, position.y))
+            .with_decorations(false) // 隐藏标题栏
+            .with_transparent(true)
+            .with_blur(false)
+            .with_active(false)
+            .with_resizable(false);
+
+        let window = event_loop.create_window(window_attributes)?;
+
+        // 关闭系统的窗口淡入淡出动画，显示与隐藏动画由 `animation` 在缓冲区中绘制
+        if let Some(hwnd) = window_hwnd(&window) {
+            let corner_preference = 1i32;
+            if let Err(e) = unsafe {
+                windows::Win32::Graphics::Dwm::DwmSetWindowAttribute(
+                    hwnd,
+                    windows::Win32::Graphics::Dwm::DWMWA_TRANSITIONS_FORCEDISABLED,
+                    &corner_preference as *const i32 as *const _,
+                    std::mem::size_of::<i32>() as u32,
+                )
+            } {
+                log::error!("Failed to set DWMWA_TRANSITIONS_FORCEDISABLED attribute: {e:?}");
+            }
+        }
+
+        let (window, _context, surface) = {
+            let window = Rc::new(window);
+            let context = softbuffer::Context::new(window.clone())
+                .map_err(|e| anyhow!("Failed to create a new instance of context - {e}"))?;
+            let surface = Surface::new(&context, window.clone())
+                .map_err(|e| anyhow!("Failed to create a surface - {e}"))?;
+            (window, context, surface)
+        };
+
+        let mut indicator_window = Self {
+            window,
+            surface,
+            size: (width, height),
+            scale,
+            content: Vec::new(),
+            colors: None,
+        };
+        indicator_window.resize_surface()?;
+
+        indicator_window.window.set_visible(true);
+        indicator_window.set_interactive(false); // 鼠标穿透
+
+        Ok(indicator_window)
+    }
+
+    pub fn hwnd(&self) -> Option<HWND> {
+        window_hwnd(&self.window)
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// 窗口在屏幕上的物理矩形
+    pub fn outer_rect(&self) -> Option<Rect> {
+        let position = self.window.outer_position().ok()?;
+        let (width, height) = self.size;
+        Some(Rect::new(
+            position.x,
+            position.y,
+            position.x + width as i32,
+            position.y + height as i32,
+        ))
+    }
+
+    /// 拖动放置期间允许点击与拖动窗口，平时鼠标穿透
+    pub fn set_interactive(&self, interactive: bool) {
+        self.window.set_enable(interactive);
+        let _ = self.window.set_cursor_hittest(interactive);
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// 移动到 `layout` 的位置，尺寸不同时调整窗口与缓冲区的大小
+    ///
+    /// 返回是否调整了大小，调整后旧画面随之失效，需要重新绘制
+    pub fn apply_layout(&mut self, layout: WindowLayout) -> Result<bool> {
+        self.scale = layout.scale;
+        self.move_to(layout.position);
+
+        if self.size == layout.size {
+            return Ok(false);
+        }
+
+        self.resize(layout.size)?;
+        Ok(true)
+    }
+
+    fn resize(&mut self, (width, height): (u32, u32)) -> Result<()> {
+        let window_size = PhysicalSize::new(width, height);
+        self.window.set_min_inner_size(Some(window_size));
+        self.window.set_max_inner_size(Some(window_size));
+        let _ = self.window.request_inner_size(window_size);
+        self.size = (width, height);
+        self.content.clear();
+        self.resize_surface()
+    }
+
+    fn move_to(&self, position: Point) {
+        self.window
+            .set_outer_position(PhysicalPosition::new(position.x, position.y));
+    }
+
+    pub fn colors(&self) -> Option<IndicatorColors> {
+        self.colors
+    }
+
+    /// 设置以 `colors` 绘制的画面
+    pub fn set_content(&mut self, content: Vec<u32>, colors: IndicatorColors) {
+        self.content = content;
+        self.colors = Some(colors);
+    }
+
+    /// 将 `content` 按 `frame` 变换后绘制到窗口
+    pub fn present(&mut self, frame: Frame) {
+        let (width, height) = self.size();
+
+        let mut buffer = self.surface.buffer_mut().unwrap();
+        self.content.resize(buffer.len(), 0);
+        apply_frame(&self.content, &mut buffer, width, height, frame);
+
+        buffer.present().expect("Failed to present the buffer");
+    }
+
+    fn resize_surface(&mut self) -> Result<()> {
+        let (width, height) = self.size;
+        self.surface
+            .resize(
+                NonZeroU32::new(width).with_context(|| "Width must be non-zero")?,
+                NonZeroU32::new(height).with_context(|| "Hight must be non-zero")?,
+            )
+            .map_err(|e| anyhow!("Failed to set the size of the buffer - {e}"))
+    }
+}
+
+fn window_hwnd(window: &Window) -> Option<HWND> {
+    match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Win32(win32_handle) => Some(HWND(win32_handle.hwnd.get() as *mut _)),
+        _ => None,
+    }
+}
@@ -0,0 +1,469 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{Receiver, RecvTimeoutError},
+};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform::KeySource;
+
+/// 轮询模式下读取锁定键状态的间隔
+pub const POLLING_INTERVAL: Duration = Duration::from_millis(150);
+
+/// 键盘钩子收到锁定键事件后，等待系统更新切换状态并合并连续事件的时间
+pub const HOOK_DEBOUNCE: Duration = Duration::from_millis(20);
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KeyDetection {
+    /// 低级键盘钩子（WH_KEYBOARD_LL），锁定键变化时立即重绘
+    #[default]
+    Hook,
+    /// 定时轮询 `GetKeyState`，钩子安装失败时也会回退到此模式
+    Polling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LockKey {
+    Caps,
+    Num,
+    Scroll,
+}
+
+impl LockKey {
+    pub const ALL: [LockKey; 3] = [LockKey::Caps, LockKey::Num, LockKey::Scroll];
+
+    /// https://learn.microsoft.com/zh-cn/windows/win32/inputdev/virtual-key-codes
+    pub fn virtual_key(&self) -> i32 {
+        match self {
+            LockKey::Caps => 0x14,
+            LockKey::Num => 0x90,
+            LockKey::Scroll => 0x91,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockKey::Caps => "Caps Lock",
+            LockKey::Num => "Num Lock",
+            LockKey::Scroll => "Scroll Lock",
+        }
+    }
+
+    /// 自定义图标的文件名前缀，如 `capslock.png`、`numlock_dark.png`
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            LockKey::Caps => "capslock",
+            LockKey::Num => "numlock",
+            LockKey::Scroll => "scrolllock",
+        }
+    }
+
+    pub fn default_glyph(&self) -> char {
+        match self {
+            LockKey::Caps => '\u{1F512}',   // 🔒
+            LockKey::Num => '\u{1F522}',    // 🔢
+            LockKey::Scroll => '\u{1F4DC}', // 📜
+        }
+    }
+
+    /// 文字标签模式下默认绘制的文字
+    pub fn default_label(&self) -> &'static str {
+        match self {
+            LockKey::Caps => "CAPS",
+            LockKey::Num => "NUM",
+            LockKey::Scroll => "SCRL",
+        }
+    }
+
+    /// 所有字体都无法绘制 `glyph` 时，使用内置字体绘制的字符
+    pub fn fallback_glyph(&self) -> char {
+        match self {
+            LockKey::Caps => '\u{21EA}',   // ⇪
+            LockKey::Num => '\u{21ED}',    // ⇭
+            LockKey::Scroll => '\u{21F3}', // ⇳
+        }
+    }
+
+    fn bit(&self) -> u8 {
+        match self {
+            LockKey::Caps => 0b001,
+            LockKey::Num => 0b010,
+            LockKey::Scroll => 0b100,
+        }
+    }
+}
+
+/// 各锁定键的开关状态（按位存储，便于放入 `AtomicU8` 跨线程共享）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStates(u8);
+
+impl KeyStates {
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits & 0b111)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_on(&self, key: LockKey) -> bool {
+        self.0 & key.bit() != 0
+    }
+
+    pub fn set(&mut self, key: LockKey, on: bool) {
+        if on {
+            self.0 |= key.bit();
+        } else {
+            self.0 &= !key.bit();
+        }
+    }
+
+    /// 只有 `keys` 开启的状态
+    #[cfg(test)]
+    pub fn from_keys(keys: &[LockKey]) -> Self {
+        let mut states = Self::default();
+        keys.iter().for_each(|key| states.set(*key, true));
+        states
+    }
+}
+
+/// 对键盘事件去抖，并比较前后两次读取的状态，仅在状态变化时返回新状态
+pub struct KeyWatcher<S: KeySource> {
+    source: S,
+    last: KeyStates,
+    debounce: Duration,
+    pending_since: Option<Instant>,
+}
+
+impl<S: KeySource> KeyWatcher<S> {
+    pub fn new(source: S, debounce: Duration) -> Self {
+        let last = source.key_states();
+        Self {
+            source,
+            last,
+            debounce,
+            pending_since: None,
+        }
+    }
+
+    pub fn last(&self) -> KeyStates {
+        self.last
+    }
+
+    /// 记录一次键盘事件，去抖时间内的后续事件合并为一次读取
+    pub fn notify(&mut self, now: Instant) {
+        self.pending_since.get_or_insert(now);
+    }
+
+    /// 距离下一次读取还需等待的时间，`None` 表示没有待处理的事件
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.pending_since
+            .map(|since| self.debounce.saturating_sub(now.duration_since(since)))
+    }
+
+    /// 处理已到期的事件，状态变化时返回新状态
+    pub fn poll(&mut self, now: Instant) -> Option<KeyStates> {
+        match self.timeout(now) {
+            Some(remaining) if remaining.is_zero() => {
+                self.pending_since = None;
+                self.check()
+            }
+            _ => None,
+        }
+    }
+
+    /// 立即读取状态（轮询模式），状态变化时返回新状态
+    pub fn check(&mut self) -> Option<KeyStates> {
+        let current = self.source.key_states();
+        if current != self.last {
+            self.last = current;
+            Some(current)
+        } else {
+            None
+        }
+    }
+}
+
+/// 监听锁定键，状态变化时调用 `publish`，直到 `exit` 被设置
+///
+/// 先阻塞等待 `receiver` 中的钩子事件；钩子安装失败、钩子线程退出或未启用钩子时
+/// 发送端被丢弃，接收端断开后回退到轮询
+pub fn watch_lock_keys<S: KeySource>(
+    watcher: &mut KeyWatcher<S>,
+    receiver: Receiver<()>,
+    exit: &AtomicBool,
+    mut publish: impl FnMut(KeyStates),
+) {
+    while !exit.load(Ordering::Relaxed) {
+        let event = match watcher.timeout(Instant::now()) {
+            Some(timeout) => receiver.recv_timeout(timeout),
+            None => receiver.recv().map_err(RecvTimeoutError::from),
+        };
+
+        match event {
+            Ok(()) => watcher.notify(Instant::now()),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Some(states) = watcher.poll(Instant::now()) {
+            publish(states);
+        }
+    }
+
+    while !exit.load(Ordering::Relaxed) {
+        std::thread::sleep(POLLING_INTERVAL);
+        if let Some(states) = watcher.check() {
+            publish(states);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeySetting {
+    pub enabled: bool,
+    /// 未提供自定义图标时绘制的字符或文字，也可以写成码位，如 `"U+1F512"`
+    #[serde(deserialize_with = "deserialize_glyph")]
+    pub glyph: String,
+    /// 文字标签模式下绘制的文字，为空时使用默认文字
+    #[serde(default)]
+    pub label: String,
+}
+
+impl KeySetting {
+    fn new(key: LockKey, enabled: bool) -> Self {
+        Self {
+            enabled,
+            glyph: key.default_glyph().to_string(),
+            label: key.default_label().to_owned(),
+        }
+    }
+}
+
+/// 将 `U+XXXX` 形式的码位（可用空格分隔多个）转换为字符，其他内容原样返回
+pub fn parse_glyph(value: &str) -> String {
+    let codepoints = value
+        .split_whitespace()
+        .map(|part| {
+            part.strip_prefix("U+")
+                .or_else(|| part.strip_prefix("u+"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+        })
+        .collect::<Option<String>>();
+
+    match codepoints {
+        Some(glyph) if !glyph.is_empty() => glyph,
+        _ => value.to_owned(),
+    }
+}
+
+fn deserialize_glyph<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|value| parse_glyph(&value))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchedKeys {
+    pub caps_lock: KeySetting,
+    pub num_lock: KeySetting,
+    pub scroll_lock: KeySetting,
+}
+
+impl Default for WatchedKeys {
+    fn default() -> Self {
+        Self {
+            caps_lock: KeySetting::new(LockKey::Caps, true),
+            num_lock: KeySetting::new(LockKey::Num, false),
+            scroll_lock: KeySetting::new(LockKey::Scroll, false),
+        }
+    }
+}
+
+impl WatchedKeys {
+    pub fn get(&self, key: LockKey) -> &KeySetting {
+        match key {
+            LockKey::Caps => &self.caps_lock,
+            LockKey::Num => &self.num_lock,
+            LockKey::Scroll => &self.scroll_lock,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: LockKey) -> &mut KeySetting {
+        match key {
+            LockKey::Caps => &mut self.caps_lock,
+            LockKey::Num => &mut self.num_lock,
+            LockKey::Scroll => &mut self.scroll_lock,
+        }
+    }
+
+    /// 返回需要显示指示器的锁定键（已启用且处于开启状态），按 `LockKey::ALL` 的顺序排列
+    pub fn active_indicators(&self, states: KeyStates) -> Vec<LockKey> {
+        LockKey::ALL
+            .into_iter()
+            .filter(|key| self.get(*key).enabled && states.is_on(*key))
+            .collect()
+    }
+}
+
+/// 将窗口横向等分为 `count` 个格子，返回每个格子的 `(x, width)`
+///
+/// 除不尽的像素分配给最后一个格子，保证格子铺满整个窗口
+pub fn layout_indicators(window_width: u32, count: usize) -> Vec<(u32, u32)> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let cell_width = window_width / count as u32;
+    (0..count as u32)
+        .map(|i| {
+            let x = i * cell_width;
+            let width = if i + 1 == count as u32 {
+                window_width - x
+            } else {
+                cell_width
+            };
+            (x, width)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{KeyHook, fake::FakePlatform};
+
+    #[test]
+    fn active_indicators_only_include_enabled_keys_that_are_on() {
+        let mut keys = WatchedKeys::default();
+        let all_on = KeyStates::from_keys(&LockKey::ALL);
+
+        assert_eq!(keys.active_indicators(all_on), vec![LockKey::Caps]);
+        assert!(
+            keys.active_indicators(KeyStates::from_keys(&[LockKey::Num]))
+                .is_empty()
+        );
+
+        keys.get_mut(LockKey::Scroll).enabled = true;
+        assert_eq!(
+            keys.active_indicators(all_on),
+            vec![LockKey::Caps, LockKey::Scroll]
+        );
+    }
+
+    #[test]
+    fn layout_indicators_fills_the_window() {
+        assert!(layout_indicators(200, 0).is_empty());
+        assert_eq!(layout_indicators(200, 1), vec![(0, 200)]);
+        assert_eq!(
+            layout_indicators(200, 3),
+            vec![(0, 66), (66, 66), (132, 68)]
+        );
+    }
+
+    #[test]
+    fn glyph_accepts_codepoints_or_text() {
+        assert_eq!(parse_glyph("U+1F512"), "\u{1F512}");
+        assert_eq!(parse_glyph("U+0041 u+0042"), "AB");
+        assert_eq!(parse_glyph("CAPS"), "CAPS");
+        assert_eq!(parse_glyph("U+ZZZZ"), "U+ZZZZ");
+
+        let setting: KeySetting = toml::from_str(
+            r#"
+            enabled = true
+            glyph = "U+21EA"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(setting.glyph, "\u{21EA}");
+    }
+
+    #[test]
+    fn check_reports_only_changes() {
+        let platform = FakePlatform::default();
+        let mut watcher = KeyWatcher::new(&platform, HOOK_DEBOUNCE);
+
+        assert_eq!(watcher.check(), None);
+
+        platform
+            .key_states
+            .set(KeyStates::from_keys(&[LockKey::Caps]));
+        assert_eq!(
+            watcher.check(),
+            Some(KeyStates::from_keys(&[LockKey::Caps]))
+        );
+        assert_eq!(watcher.check(), None);
+    }
+
+    #[test]
+    fn poll_waits_for_debounce_and_coalesces_events() {
+        let platform = FakePlatform::default();
+        let mut watcher = KeyWatcher::new(&platform, HOOK_DEBOUNCE);
+        let start = Instant::now();
+        let half = HOOK_DEBOUNCE / 2;
+
+        assert_eq!(watcher.timeout(start), None);
+
+        watcher.notify(start);
+        platform
+            .key_states
+            .set(KeyStates::from_keys(&[LockKey::Caps]));
+        watcher.notify(start + half);
+
+        assert_eq!(watcher.poll(start + half), None);
+        assert_eq!(watcher.timeout(start + half), Some(half));
+
+        assert_eq!(
+            watcher.poll(start + HOOK_DEBOUNCE),
+            Some(KeyStates::from_keys(&[LockKey::Caps]))
+        );
+        assert_eq!(watcher.timeout(start + HOOK_DEBOUNCE), None);
+        assert_eq!(watcher.last(), KeyStates::from_keys(&[LockKey::Caps]));
+    }
+
+    #[test]
+    fn stopping_the_hook_disconnects_the_receiver() {
+        let platform = FakePlatform::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        platform.run_key_hook(sender).unwrap();
+        platform
+            .key_hook_sender
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .send(())
+            .unwrap();
+        assert_eq!(receiver.recv(), Ok(()));
+
+        platform.stop_key_hook();
+        assert!(receiver.recv().is_err());
+    }
+
+    #[test]
+    fn falls_back_to_polling_when_the_hook_fails_to_install() {
+        let platform = FakePlatform {
+            key_hook_fails: true,
+            ..Default::default()
+        };
+        let mut watcher = KeyWatcher::new(&platform, HOOK_DEBOUNCE);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        assert!(platform.run_key_hook(sender).is_err());
+        platform
+            .key_states
+            .set(KeyStates::from_keys(&[LockKey::Caps]));
+
+        // 钩子安装失败时发送端已被丢弃，不会一直阻塞等待钩子事件
+        let exit = AtomicBool::new(false);
+        let mut published = Vec::new();
+        watch_lock_keys(&mut watcher, receiver, &exit, |states| {
+            published.push(states);
+            exit.store(true, Ordering::Relaxed);
+        });
+
+        assert_eq!(published, vec![KeyStates::from_keys(&[LockKey::Caps])]);
+    }
+}
@@ -42,6 +42,11 @@ pub struct Localization {
     pub theme: &'static str,
     pub follow_system_theme: &'static str,
     pub follow_indicator_area_theme: &'static str,
+    pub follow_contrast_theme: &'static str,
+    pub follow_accent_theme: &'static str,
+    pub palette_default: &'static str,
+    pub palette_vivid: &'static str,
+    pub palette_soft: &'static str,
     //
     pub position: &'static str,
     pub position_center: &'static str,
@@ -53,10 +58,40 @@ pub struct Localization {
     pub position_top_right: &'static str,
     pub position_bottom_left: &'static str,
     pub position_bottom_right: &'static str,
+    pub position_caret: &'static str,
+    pub position_cursor: &'static str,
+    pub position_custom: &'static str,
+    pub place_by_dragging: &'static str,
     //
     pub select_monitor: &'static str,
     pub select_primary_monitor: &'static str,
     pub select_mouse_monitor: &'static str,
+    pub select_all_monitors: &'static str,
+    pub monitor_disconnected: &'static str,
+    //
+    pub lock_keys: &'static str,
+    //
+    pub style: &'static str,
+    pub style_glyph: &'static str,
+    pub style_text: &'static str,
+    pub font_weight: &'static str,
+    pub font_weight_light: &'static str,
+    pub font_weight_regular: &'static str,
+    pub font_weight_semibold: &'static str,
+    pub font_weight_bold: &'static str,
+    pub background_plate: &'static str,
+    pub indicator_size: &'static str,
+    pub size_small: &'static str,
+    pub size_medium: &'static str,
+    pub size_large: &'static str,
+    //
+    pub display_mode: &'static str,
+    pub display_persistent: &'static str,
+    pub display_osd: &'static str,
+    pub osd_duration: &'static str,
+    pub osd_show_off: &'static str,
+    //
+    pub invalid_config: &'static str,
 }
 
 const ZH_CN: Localization = Localization {
@@ -77,6 +112,11 @@ const ZH_CN: Localization = Localization {
     theme: "主题",
     follow_system_theme: "跟随系统主题",
     follow_indicator_area_theme: "跟随指示器区域主题",
+    follow_contrast_theme: "跟随背景颜色（高对比度）",
+    follow_accent_theme: "跟随强调色",
+    palette_default: "默认配色",
+    palette_vivid: "鲜艳配色",
+    palette_soft: "柔和配色",
     //
     position: "显示位置",
     position_center: "中央",
@@ -88,10 +128,40 @@ const ZH_CN: Localization = Localization {
     position_top_right: "右上角",
     position_bottom_left: "左下角",
     position_bottom_right: "右下角",
+    position_caret: "跟随文字光标",
+    position_cursor: "跟随鼠标指针",
+    position_custom: "自定义位置",
+    place_by_dragging: "拖动放置…",
     //
     select_monitor: "显示屏幕",
     select_primary_monitor: "主屏幕",
     select_mouse_monitor: "鼠标下屏幕",
+    select_all_monitors: "所有屏幕",
+    monitor_disconnected: "已断开的显示器",
+    //
+    lock_keys: "锁定键",
+    //
+    style: "样式",
+    style_glyph: "图标",
+    style_text: "文字",
+    font_weight: "字重",
+    font_weight_light: "细体",
+    font_weight_regular: "常规",
+    font_weight_semibold: "半粗体",
+    font_weight_bold: "粗体",
+    background_plate: "背景",
+    indicator_size: "大小",
+    size_small: "小",
+    size_medium: "中",
+    size_large: "大",
+    //
+    display_mode: "显示方式",
+    display_persistent: "常驻显示",
+    display_osd: "切换时短暂显示",
+    osd_duration: "显示时长",
+    osd_show_off: "显示关闭状态",
+    //
+    invalid_config: "配置文件有错误，已使用默认设置。修正前不会改动该文件：",
 };
 
 const ZH_HANT: Localization = Localization {
@@ -112,6 +182,11 @@ const ZH_HANT: Localization = Localization {
     theme: "主題",
     follow_system_theme: "跟隨系統主題",
     follow_indicator_area_theme: "跟隨指示器區域主題",
+    follow_contrast_theme: "跟隨背景顏色（高對比度）",
+    follow_accent_theme: "跟隨強調色",
+    palette_default: "預設配色",
+    palette_vivid: "鮮豔配色",
+    palette_soft: "柔和配色",
     position: "顯示位置",
     //
     position_center: "中央",
@@ -123,10 +198,40 @@ const ZH_HANT: Localization = Localization {
     position_top_right: "右上角",
     position_bottom_left: "左下角",
     position_bottom_right: "右下角",
+    position_caret: "跟隨文字游標",
+    position_cursor: "跟隨滑鼠指標",
+    position_custom: "自訂位置",
+    place_by_dragging: "拖曳放置…",
     //
     select_monitor: "顯示螢幕",
     select_primary_monitor: "主螢幕",
     select_mouse_monitor: "滑鼠所在螢幕",
+    select_all_monitors: "所有螢幕",
+    monitor_disconnected: "已中斷連線的螢幕",
+    //
+    lock_keys: "鎖定鍵",
+    //
+    style: "樣式",
+    style_glyph: "圖示",
+    style_text: "文字",
+    font_weight: "字重",
+    font_weight_light: "細體",
+    font_weight_regular: "標準",
+    font_weight_semibold: "半粗體",
+    font_weight_bold: "粗體",
+    background_plate: "背景",
+    indicator_size: "大小",
+    size_small: "小",
+    size_medium: "中",
+    size_large: "大",
+    //
+    display_mode: "顯示方式",
+    display_persistent: "常駐顯示",
+    display_osd: "切換時短暫顯示",
+    osd_duration: "顯示時長",
+    osd_show_off: "顯示關閉狀態",
+    //
+    invalid_config: "設定檔有錯誤，已使用預設設定。修正前不會變更該檔案：",
 };
 
 const EN_US: Localization = Localization {
@@ -147,6 +252,11 @@ const EN_US: Localization = Localization {
     theme: "Theme",
     follow_system_theme: "Follow System Theme",
     follow_indicator_area_theme: "Follow Indicator Area Theme",
+    follow_contrast_theme: "Contrast with Background Color",
+    follow_accent_theme: "Follow Accent Color",
+    palette_default: "Default Colors",
+    palette_vivid: "Vivid Colors",
+    palette_soft: "Soft Colors",
     //
     position: "Position",
     position_center: "Center",
@@ -158,10 +268,40 @@ const EN_US: Localization = Localization {
     position_top_right: "Top Right",
     position_bottom_left: "Bottom Left",
     position_bottom_right: "Bottom Right",
+    position_caret: "Follow Text Caret",
+    position_cursor: "Follow Mouse Cursor",
+    position_custom: "Custom Position",
+    place_by_dragging: "Drag to Place…",
     //
     select_monitor: "Select Monitor",
     select_primary_monitor: "Primary Monitor",
     select_mouse_monitor: "Monitor under Mouse",
+    select_all_monitors: "All Monitors",
+    monitor_disconnected: "Disconnected Monitor",
+    //
+    lock_keys: "Lock Keys",
+    //
+    style: "Style",
+    style_glyph: "Icon",
+    style_text: "Text",
+    font_weight: "Font Weight",
+    font_weight_light: "Light",
+    font_weight_regular: "Regular",
+    font_weight_semibold: "Semibold",
+    font_weight_bold: "Bold",
+    background_plate: "Background Plate",
+    indicator_size: "Size",
+    size_small: "Small",
+    size_medium: "Medium",
+    size_large: "Large",
+    //
+    display_mode: "Display Mode",
+    display_persistent: "Persistent",
+    display_osd: "On-Screen Display",
+    osd_duration: "Display Duration",
+    osd_show_off: "Show OFF State",
+    //
+    invalid_config: "The config file is invalid, so the default settings are used. The file will not be changed until it is fixed:",
 };
 
 const JA_JP: Localization = Localization {
@@ -182,6 +322,11 @@ const JA_JP: Localization = Localization {
     theme: "テーマ",
     follow_system_theme: "システムテーマに従う",
     follow_indicator_area_theme: "インジケーターエリアのテーマに従う",
+    follow_contrast_theme: "背景色とのコントラスト",
+    follow_accent_theme: "アクセントカラーに従う",
+    palette_default: "標準の配色",
+    palette_vivid: "鮮やかな配色",
+    palette_soft: "やわらかい配色",
     //
     position: "表示位置",
     position_center: "中央",
@@ -193,10 +338,40 @@ const JA_JP: Localization = Localization {
     position_top_right: "右上",
     position_bottom_left: "左下",
     position_bottom_right: "右下",
+    position_caret: "テキストカーソルに追従",
+    position_cursor: "マウスカーソルに追従",
+    position_custom: "カスタム位置",
+    place_by_dragging: "ドラッグして配置…",
     //
     select_monitor: "モニターを選択",
     select_primary_monitor: "メインモニター",
     select_mouse_monitor: "マウス下のモニター",
+    select_all_monitors: "すべての画面",
+    monitor_disconnected: "切断されたモニター",
+    //
+    lock_keys: "ロックキー",
+    //
+    style: "スタイル",
+    style_glyph: "アイコン",
+    style_text: "テキスト",
+    font_weight: "フォントの太さ",
+    font_weight_light: "細字",
+    font_weight_regular: "標準",
+    font_weight_semibold: "セミボールド",
+    font_weight_bold: "太字",
+    background_plate: "背景プレート",
+    indicator_size: "サイズ",
+    size_small: "小",
+    size_medium: "中",
+    size_large: "大",
+    //
+    display_mode: "表示モード",
+    display_persistent: "常時表示",
+    display_osd: "切り替え時に表示",
+    osd_duration: "表示時間",
+    osd_show_off: "オフ状態を表示",
+    //
+    invalid_config: "設定ファイルにエラーがあるため、既定の設定を使用しています。修正されるまでファイルは変更されません：",
 };
 
 const KO_KR: Localization = Localization {
@@ -217,6 +392,11 @@ const KO_KR: Localization = Localization {
     theme: "테마",
     follow_system_theme: "시스템 테마 따르기",
     follow_indicator_area_theme: "인디케이터 영역 테마 따르기",
+    follow_contrast_theme: "배경색과 대비",
+    follow_accent_theme: "강조 색 따르기",
+    palette_default: "기본 색상",
+    palette_vivid: "선명한 색상",
+    palette_soft: "부드러운 색상",
     position: "표시 위치",
     //
     position_center: "중앙",
@@ -228,10 +408,40 @@ const KO_KR: Localization = Localization {
     position_top_right: "오른쪽 위",
     position_bottom_left: "왼쪽 아래",
     position_bottom_right: "오른쪽 아래",
+    position_caret: "텍스트 커서 따라가기",
+    position_cursor: "마우스 커서 따라가기",
+    position_custom: "사용자 지정 위치",
+    place_by_dragging: "드래그하여 배치…",
     //
     select_monitor: "모니터 선택",
     select_primary_monitor: "기본 모니터",
     select_mouse_monitor: "마우스 아래 모니터",
+    select_all_monitors: "모든 화면",
+    monitor_disconnected: "연결 끊긴 모니터",
+    //
+    lock_keys: "잠금 키",
+    //
+    style: "스타일",
+    style_glyph: "아이콘",
+    style_text: "텍스트",
+    font_weight: "글꼴 굵기",
+    font_weight_light: "가늘게",
+    font_weight_regular: "보통",
+    font_weight_semibold: "세미볼드",
+    font_weight_bold: "굵게",
+    background_plate: "배경 판",
+    indicator_size: "크기",
+    size_small: "작게",
+    size_medium: "보통",
+    size_large: "크게",
+    //
+    display_mode: "표시 모드",
+    display_persistent: "항상 표시",
+    display_osd: "전환 시 잠깐 표시",
+    osd_duration: "표시 시간",
+    osd_show_off: "꺼짐 상태 표시",
+    //
+    invalid_config: "설정 파일에 오류가 있어 기본 설정을 사용합니다. 수정될 때까지 파일은 변경되지 않습니다:",
 };
 
 const DE_DE: Localization = Localization {
@@ -252,6 +462,11 @@ const DE_DE: Localization = Localization {
     theme: "Thema",
     follow_system_theme: "Systemthema folgen",
     follow_indicator_area_theme: "Indikatorbereichsthema folgen",
+    follow_contrast_theme: "Kontrast zur Hintergrundfarbe",
+    follow_accent_theme: "Akzentfarbe folgen",
+    palette_default: "Standardfarben",
+    palette_vivid: "Kräftige Farben",
+    palette_soft: "Sanfte Farben",
     //
     position: "Position",
     position_center: "Mitte",
@@ -263,10 +478,40 @@ const DE_DE: Localization = Localization {
     position_top_right: "Oben Rechts",
     position_bottom_left: "Unten Links",
     position_bottom_right: "Unten Rechts",
+    position_caret: "Textcursor folgen",
+    position_cursor: "Mauszeiger folgen",
+    position_custom: "Benutzerdefinierte Position",
+    place_by_dragging: "Zum Platzieren ziehen…",
     //
     select_monitor: "Monitor auswählen",
     select_primary_monitor: "Primärer Monitor",
     select_mouse_monitor: "Monitor unter Maus",
+    select_all_monitors: "Alle Bildschirme",
+    monitor_disconnected: "Getrennter Monitor",
+    //
+    lock_keys: "Sperrtasten",
+    //
+    style: "Stil",
+    style_glyph: "Symbol",
+    style_text: "Text",
+    font_weight: "Schriftstärke",
+    font_weight_light: "Leicht",
+    font_weight_regular: "Normal",
+    font_weight_semibold: "Halbfett",
+    font_weight_bold: "Fett",
+    background_plate: "Hintergrundplatte",
+    indicator_size: "Größe",
+    size_small: "Klein",
+    size_medium: "Mittel",
+    size_large: "Groß",
+    //
+    display_mode: "Anzeigemodus",
+    display_persistent: "Dauerhaft",
+    display_osd: "Bildschirmanzeige",
+    osd_duration: "Anzeigedauer",
+    osd_show_off: "AUS-Zustand anzeigen",
+    //
+    invalid_config: "Die Konfigurationsdatei ist ungültig, daher werden die Standardeinstellungen verwendet. Die Datei wird erst nach der Korrektur wieder geändert:",
 };
 
 const RU_RU: Localization = Localization {
@@ -287,6 +532,11 @@ const RU_RU: Localization = Localization {
     theme: "Тема",
     follow_system_theme: "Следовать системной теме",
     follow_indicator_area_theme: "Следовать теме области индикатора",
+    follow_contrast_theme: "Контраст с цветом фона",
+    follow_accent_theme: "Следовать цвету акцента",
+    palette_default: "Стандартные цвета",
+    palette_vivid: "Яркие цвета",
+    palette_soft: "Мягкие цвета",
     //
     position: "Положение",
     position_center: "Центр",
@@ -298,10 +548,40 @@ const RU_RU: Localization = Localization {
     position_top_right: "Верхний правый",
     position_bottom_left: "Нижний левый",
     position_bottom_right: "Нижний правый",
+    position_caret: "Следовать за текстовым курсором",
+    position_cursor: "Следовать за указателем мыши",
+    position_custom: "Своё положение",
+    place_by_dragging: "Перетащить для размещения…",
     //
     select_monitor: "Выбрать монитор",
     select_primary_monitor: "Основной монитор",
     select_mouse_monitor: "Монитор под мышью",
+    select_all_monitors: "Все экраны",
+    monitor_disconnected: "Отключённый монитор",
+    //
+    lock_keys: "Клавиши блокировки",
+    //
+    style: "Стиль",
+    style_glyph: "Значок",
+    style_text: "Текст",
+    font_weight: "Насыщенность шрифта",
+    font_weight_light: "Светлый",
+    font_weight_regular: "Обычный",
+    font_weight_semibold: "Полужирный",
+    font_weight_bold: "Жирный",
+    background_plate: "Фоновая подложка",
+    indicator_size: "Размер",
+    size_small: "Маленький",
+    size_medium: "Средний",
+    size_large: "Большой",
+    //
+    display_mode: "Режим отображения",
+    display_persistent: "Постоянно",
+    display_osd: "Экранное уведомление",
+    osd_duration: "Длительность показа",
+    osd_show_off: "Показывать выключение",
+    //
+    invalid_config: "Файл конфигурации содержит ошибки, используются настройки по умолчанию. Файл не будет изменён, пока ошибка не исправлена:",
 };
 
 const AR_SA: Localization = Localization {
@@ -322,6 +602,11 @@ const AR_SA: Localization = Localization {
     theme: "موضوع",
     follow_system_theme: "اتبع موضوع النظام",
     follow_indicator_area_theme: "اتبع موضوع منطقة المؤشر",
+    follow_contrast_theme: "التباين مع لون الخلفية",
+    follow_accent_theme: "اتباع لون التمييز",
+    palette_default: "الألوان الافتراضية",
+    palette_vivid: "ألوان زاهية",
+    palette_soft: "ألوان هادئة",
     //
     position: "الموضع",
     position_center: "الوسط",
@@ -333,10 +618,40 @@ const AR_SA: Localization = Localization {
     position_top_right: "أعلى اليمين",
     position_bottom_left: "أسفل اليسار",
     position_bottom_right: "أسفل اليمين",
+    position_caret: "اتباع مؤشر النص",
+    position_cursor: "اتباع مؤشر الماوس",
+    position_custom: "موضع مخصص",
+    place_by_dragging: "اسحب للتحديد…",
     //
     select_monitor: "اختر الشاشة",
     select_primary_monitor: "الشاشة الرئيسية",
     select_mouse_monitor: "الشاشة تحت الفأرة",
+    select_all_monitors: "جميع الشاشات",
+    monitor_disconnected: "شاشة غير متصلة",
+    //
+    lock_keys: "مفاتيح القفل",
+    //
+    style: "النمط",
+    style_glyph: "أيقونة",
+    style_text: "نص",
+    font_weight: "سماكة الخط",
+    font_weight_light: "رفيع",
+    font_weight_regular: "عادي",
+    font_weight_semibold: "شبه عريض",
+    font_weight_bold: "عريض",
+    background_plate: "لوحة خلفية",
+    indicator_size: "الحجم",
+    size_small: "صغير",
+    size_medium: "متوسط",
+    size_large: "كبير",
+    //
+    display_mode: "وضع العرض",
+    display_persistent: "دائم",
+    display_osd: "عرض على الشاشة",
+    osd_duration: "مدة العرض",
+    osd_show_off: "إظهار حالة الإيقاف",
+    //
+    invalid_config: "ملف الإعدادات غير صالح، لذلك تُستخدم الإعدادات الافتراضية. لن يتم تغيير الملف حتى يتم إصلاحه:",
 };
 
 const FR_FR: Localization = Localization {
@@ -357,6 +672,11 @@ const FR_FR: Localization = Localization {
     theme: "Thème",
     follow_system_theme: "Suivre le thème du système",
     follow_indicator_area_theme: "Suivre le thème de la zone de l'indicateur",
+    follow_contrast_theme: "Contraste avec la couleur de fond",
+    follow_accent_theme: "Suivre la couleur d'accentuation",
+    palette_default: "Couleurs par défaut",
+    palette_vivid: "Couleurs vives",
+    palette_soft: "Couleurs douces",
     //
     position: "Position",
     position_center: "Centre",
@@ -368,13 +688,44 @@ const FR_FR: Localization = Localization {
     position_top_right: "Haut droite",
     position_bottom_left: "Bas gauche",
     position_bottom_right: "Bas droite",
+    position_caret: "Suivre le curseur de texte",
+    position_cursor: "Suivre le pointeur de la souris",
+    position_custom: "Position personnalisée",
+    place_by_dragging: "Glisser pour placer…",
     //
     select_monitor: "Sélectionner l'écran",
     select_primary_monitor: "Écran principal",
     select_mouse_monitor: "Écran sous la souris",
+    select_all_monitors: "Tous les écrans",
+    monitor_disconnected: "Écran déconnecté",
+    //
+    lock_keys: "Touches de verrouillage",
+    //
+    style: "Style",
+    style_glyph: "Icône",
+    style_text: "Texte",
+    font_weight: "Graisse de police",
+    font_weight_light: "Maigre",
+    font_weight_regular: "Normal",
+    font_weight_semibold: "Demi-gras",
+    font_weight_bold: "Gras",
+    background_plate: "Plaque de fond",
+    indicator_size: "Taille",
+    size_small: "Petite",
+    size_medium: "Moyenne",
+    size_large: "Grande",
+    //
+    display_mode: "Mode d'affichage",
+    display_persistent: "Permanent",
+    display_osd: "Affichage à l'écran",
+    osd_duration: "Durée d'affichage",
+    osd_show_off: "Afficher l'état désactivé",
+    //
+    invalid_config: "Le fichier de configuration est invalide, les paramètres par défaut sont utilisés. Le fichier ne sera pas modifié tant qu'il n'est pas corrigé :",
 };
 
 impl Language {
+    #[cfg(target_os = "windows")]
     fn get_system_language() -> Language {
         let sys_lcid = unsafe { windows::Win32::Globalization::GetSystemDefaultLCID() };
 
@@ -383,6 +734,11 @@ impl Language {
             .find(|&&(lcid, ..)| lcid as u32 == sys_lcid)
             .map_or(Language::English_UnitedStates, |&(_, lang, ..)| lang)
     }
+
+    #[cfg(not(target_os = "windows"))]
+    fn get_system_language() -> Language {
+        Language::English_UnitedStates
+    }
 }
 
 #[allow(missing_docs)]
@@ -598,6 +954,7 @@ pub enum Language {
 }
 
 #[rustfmt::skip]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 static TABLE: &[(u16, Language, &str, &str)] = &[
     (0x0000, Language::Unknown, "Unknown", "Unknown"),
     (0x0436, Language::Afrikaans_SouthAfrica, "Afrikaans", "South Africa"),
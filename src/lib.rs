@@ -0,0 +1,24 @@
+//! CapsGlow 核心逻辑
+//!
+//! 配置、按键状态、定位与主题等逻辑不直接调用 Win32，而是通过 [`platform`] 中的 trait 访问系统，
+//! 因此可以在任意平台上编译与测试。窗口、托盘等界面部分位于可执行文件中，仅在 Windows 上编译。
+
+pub mod animation;
+pub mod config;
+pub mod contrast;
+pub mod font;
+pub mod icon;
+pub mod key;
+pub mod language;
+pub mod monitor;
+pub mod osd;
+pub mod palette;
+pub mod platform;
+pub mod render;
+pub mod snapshot;
+pub mod startup;
+pub mod style;
+pub mod theme;
+#[cfg(target_os = "windows")]
+pub mod util;
+pub mod window;
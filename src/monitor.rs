@@ -1,50 +1,97 @@
-use anyhow::{Result, anyhow};
+use std::time::Duration;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use windows::Win32::{
-    Foundation::{POINT, RECT},
-    Graphics::Gdi::{
-        GetDC, GetDeviceCaps, GetMonitorInfoW, LOGPIXELSX, MONITOR_DEFAULTTONEAREST, MONITORINFO,
-        MonitorFromPoint, ReleaseDC,
-    },
-    UI::WindowsAndMessaging::GetCursorPos,
-};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+use crate::platform::{MonitorArea, MonitorInfo, MonitorProvider, Point};
+
+/// 显示器插拔、分辨率或方向变化后再次定位的延迟
+///
+/// 收到通知时系统可能仍在重新排列显示器，稍后再定位一次，确保使用最终的显示器布局
+pub const DISPLAY_SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// 显示器的稳定标识
+///
+/// Windows 上为显示器的设备接口路径（包含由 EDID 得到的厂商与型号，如
+/// `\\?\DISPLAY#DEL40F3#...`），重新排列或调整分辨率后保持不变
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MonitorId(pub String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MonitorSelector {
     MouseMonitor,
     PrimaryMonitor,
+    /// 固定在指定的显示器上，该显示器断开时回退到主显示器
+    Specific(MonitorId),
+    /// 在每个显示器的相同位置同时显示，只需一个位置时使用主显示器
+    AllMonitors,
 }
 
 impl MonitorSelector {
-    pub fn get_target_monitor_phy_rect(&self) -> Result<RECT> {
-        unsafe {
-            let target_cursor = match self {
-                MonitorSelector::PrimaryMonitor => Ok(POINT { x: 0, y: 0 }),
-                MonitorSelector::MouseMonitor => {
-                    let mut point = std::mem::zeroed();
-                    GetCursorPos(&mut point).map_or_else(
-                        |e| Err(anyhow!("Failed to get cursor position: {e}")),
-                        |_| Ok(point),
-                    )
+    pub fn get_target_monitor_phy_area(
+        &self,
+        monitors: &impl MonitorProvider,
+    ) -> Result<MonitorArea> {
+        let target_cursor = match self {
+            MonitorSelector::PrimaryMonitor | MonitorSelector::AllMonitors => Point::new(0, 0),
+            MonitorSelector::MouseMonitor => monitors.cursor_position()?,
+            MonitorSelector::Specific(id) => {
+                match monitors.monitors().into_iter().find(|info| info.id == *id) {
+                    Some(info) => return Ok(info.area),
+                    None => {
+                        log::warn!(
+                            "Monitor '{}' is not connected, use the primary monitor",
+                            id.0
+                        );
+                        Point::new(0, 0)
+                    }
                 }
-            }?;
-
-            let mut info: MONITORINFO = std::mem::zeroed();
-            info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
-            let monitor = MonitorFromPoint(target_cursor, MONITOR_DEFAULTTONEAREST);
+            }
+        };
 
-            GetMonitorInfoW(monitor, &mut info).ok()?;
+        monitors.monitor_area_from_point(target_cursor)
+    }
+}
 
-            Ok(info.rcMonitor)
-        }
+impl MonitorInfo {
+    /// 托盘菜单中显示的名称，如 `2. DELL U2419H (1920×1080)`，`index` 从 0 开始
+    pub fn label(&self, index: usize) -> String {
+        format!(
+            "{}. {} ({}×{})",
+            index + 1,
+            self.name,
+            self.area.monitor.width(),
+            self.area.monitor.height()
+        )
     }
 }
 
-pub fn get_scale_factor() -> f64 {
-    unsafe {
-        let hdc = GetDC(None);
-        let dpi = GetDeviceCaps(Some(hdc), LOGPIXELSX) as f64;
-        ReleaseDC(None, hdc);
-        dpi / 96.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{Rect, fake::FakePlatform};
+
+    #[test]
+    fn specific_monitor_falls_back_to_primary_when_disconnected() {
+        let secondary = Rect::new(1920, 0, 3840, 1080);
+        let connected = FakePlatform::with_monitors(vec![Rect::new(0, 0, 1920, 1080), secondary]);
+        let id = connected.monitors()[1].id.clone();
+        let selector = MonitorSelector::Specific(id);
+
+        assert_eq!(
+            selector.get_target_monitor_phy_area(&connected).unwrap(),
+            MonitorArea::new(secondary)
+        );
+        assert_eq!(
+            connected.monitors()[1].label(1),
+            "2. Fake Monitor (1920×1080)"
+        );
+
+        let disconnected = FakePlatform::default();
+        assert_eq!(
+            selector.get_target_monitor_phy_area(&disconnected).unwrap(),
+            MonitorArea::new(Rect::new(0, 0, 1920, 1080))
+        );
     }
 }
@@ -0,0 +1,260 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::key::{KeyStates, LockKey, WatchedKeys};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    /// 锁定键开启期间一直显示指示器
+    #[default]
+    Persistent,
+    /// 每次切换锁定键后短暂显示开关状态，随后自动隐藏
+    Osd,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySetting {
+    pub mode: DisplayMode,
+    /// OSD 模式下每次切换后显示的时长（毫秒）
+    pub osd_duration_ms: u64,
+    /// OSD 模式下锁定键关闭时是否显示 `OFF`
+    pub osd_show_off: bool,
+    /// 没有指示器需要显示后，隐藏指示器窗口的延迟（毫秒）
+    pub hide_window_delay_ms: u64,
+}
+
+impl Default for DisplaySetting {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::default(),
+            osd_duration_ms: 1500,
+            osd_show_off: true,
+            hide_window_delay_ms: 60_000,
+        }
+    }
+}
+
+impl DisplaySetting {
+    pub fn osd_duration(&self) -> Duration {
+        Duration::from_millis(self.osd_duration_ms)
+    }
+
+    pub fn hide_window_delay(&self) -> Duration {
+        Duration::from_millis(self.hide_window_delay_ms)
+    }
+}
+
+/// 窗口中的一个指示器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indicator {
+    /// 常驻模式：绘制锁定键的图标或文字
+    Key(LockKey),
+    /// OSD 模式：绘制锁定键切换后的状态，如 `Caps Lock ON`
+    Toggle(LockKey, bool),
+}
+
+impl Indicator {
+    pub fn key(&self) -> LockKey {
+        match self {
+            Indicator::Key(key) | Indicator::Toggle(key, _) => *key,
+        }
+    }
+}
+
+/// 指示器的显示状态机：比较前后两次锁定键状态，决定当前需要绘制哪些指示器
+#[derive(Debug, Default)]
+pub struct IndicatorVisibility {
+    last_states: Option<KeyStates>,
+    toggles: Vec<Indicator>,
+    hide_at: Option<Instant>,
+}
+
+impl IndicatorVisibility {
+    /// 记录最新的锁定键状态，返回此刻需要绘制的指示器（按 `LockKey::ALL` 的顺序排列）
+    ///
+    /// 首次调用只记录状态，启动时不会弹出 OSD
+    pub fn update(
+        &mut self,
+        setting: &DisplaySetting,
+        watched_keys: &WatchedKeys,
+        states: KeyStates,
+        now: Instant,
+    ) -> Vec<Indicator> {
+        let previous = self.last_states.replace(states);
+
+        if setting.mode == DisplayMode::Persistent {
+            self.toggles.clear();
+            self.hide_at = None;
+
+            return watched_keys
+                .active_indicators(states)
+                .into_iter()
+                .map(Indicator::Key)
+                .collect();
+        }
+
+        if self.hide_at.is_some_and(|hide_at| now >= hide_at) {
+            self.toggles.clear();
+            self.hide_at = None;
+        }
+
+        let toggled_keys = LockKey::ALL.into_iter().filter(|key| {
+            watched_keys.get(*key).enabled
+                && previous.is_some_and(|previous| previous.is_on(*key) != states.is_on(*key))
+        });
+
+        for key in toggled_keys {
+            let on = states.is_on(key);
+            self.toggles.retain(|indicator| indicator.key() != key);
+            if on || setting.osd_show_off {
+                self.toggles.push(Indicator::Toggle(key, on));
+            }
+            self.hide_at = Some(now + setting.osd_duration());
+        }
+
+        self.toggles.sort_by_key(|indicator| indicator.key());
+
+        if self.toggles.is_empty() {
+            self.hide_at = None;
+        }
+
+        self.toggles.clone()
+    }
+
+    /// OSD 需要自动隐藏的时间，到期后应再次调用 `update`
+    pub fn deadline(&self) -> Option<Instant> {
+        self.hide_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn osd(show_off: bool) -> DisplaySetting {
+        DisplaySetting {
+            mode: DisplayMode::Osd,
+            osd_duration_ms: 1000,
+            osd_show_off: show_off,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn persistent_mode_shows_keys_while_on() {
+        let mut visibility = IndicatorVisibility::default();
+        let setting = DisplaySetting::default();
+        let keys = WatchedKeys::default();
+        let now = Instant::now();
+
+        assert_eq!(
+            visibility.update(&setting, &keys, KeyStates::from_keys(&[LockKey::Caps]), now),
+            vec![Indicator::Key(LockKey::Caps)]
+        );
+        assert!(
+            visibility
+                .update(&setting, &keys, KeyStates::from_keys(&[]), now)
+                .is_empty()
+        );
+        assert_eq!(visibility.deadline(), None);
+    }
+
+    #[test]
+    fn osd_mode_shows_toggles_until_the_deadline() {
+        let mut visibility = IndicatorVisibility::default();
+        let setting = osd(true);
+        let keys = WatchedKeys::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // 启动时不显示
+        assert!(
+            visibility
+                .update(
+                    &setting,
+                    &keys,
+                    KeyStates::from_keys(&[LockKey::Caps]),
+                    start
+                )
+                .is_empty()
+        );
+
+        assert_eq!(
+            visibility.update(&setting, &keys, KeyStates::from_keys(&[]), at(100)),
+            vec![Indicator::Toggle(LockKey::Caps, false)]
+        );
+        assert_eq!(visibility.deadline(), Some(at(1100)));
+
+        // 未启用的锁定键不会弹出 OSD，也不会延长显示时间
+        assert_eq!(
+            visibility.update(
+                &setting,
+                &keys,
+                KeyStates::from_keys(&[LockKey::Num]),
+                at(500)
+            ),
+            vec![Indicator::Toggle(LockKey::Caps, false)]
+        );
+        assert_eq!(visibility.deadline(), Some(at(1100)));
+
+        assert_eq!(
+            visibility.update(
+                &setting,
+                &keys,
+                KeyStates::from_keys(&[LockKey::Caps, LockKey::Num]),
+                at(800)
+            ),
+            vec![Indicator::Toggle(LockKey::Caps, true)]
+        );
+        assert_eq!(visibility.deadline(), Some(at(1800)));
+
+        assert!(
+            visibility
+                .update(
+                    &setting,
+                    &keys,
+                    KeyStates::from_keys(&[LockKey::Caps, LockKey::Num]),
+                    at(1800)
+                )
+                .is_empty()
+        );
+        assert_eq!(visibility.deadline(), None);
+    }
+
+    #[test]
+    fn osd_can_skip_the_off_state() {
+        let mut visibility = IndicatorVisibility::default();
+        let setting = osd(false);
+        let mut keys = WatchedKeys::default();
+        keys.get_mut(LockKey::Num).enabled = true;
+        let now = Instant::now();
+
+        visibility.update(&setting, &keys, KeyStates::from_keys(&[]), now);
+        assert_eq!(
+            visibility.update(
+                &setting,
+                &keys,
+                KeyStates::from_keys(&[LockKey::Caps, LockKey::Num]),
+                now
+            ),
+            vec![
+                Indicator::Toggle(LockKey::Caps, true),
+                Indicator::Toggle(LockKey::Num, true)
+            ]
+        );
+
+        // 关闭时移除该键的 OSD，其余键继续显示
+        assert_eq!(
+            visibility.update(&setting, &keys, KeyStates::from_keys(&[LockKey::Num]), now),
+            vec![Indicator::Toggle(LockKey::Num, true)]
+        );
+        assert!(
+            visibility
+                .update(&setting, &keys, KeyStates::from_keys(&[]), now)
+                .is_empty()
+        );
+        assert_eq!(visibility.deadline(), None);
+    }
+}
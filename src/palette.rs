@@ -0,0 +1,192 @@
+//! 内置字形与文字标签的调色板
+
+use std::fmt;
+
+use image::Rgba;
+use serde::{Deserialize, Serialize};
+
+use crate::key::LockKey;
+use crate::theme::SystemTheme;
+
+/// 配置文件中的颜色，格式为 `#RRGGBB` 或带透明度的 `#RRGGBBAA`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(pub Rgba<u8>);
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let error = || format!("invalid colour '{value}', expected #RRGGBB or #RRGGBBAA");
+
+        let hex = value.strip_prefix('#').ok_or_else(error)?;
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return Err(error());
+        }
+
+        let channel = |index: usize| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16);
+        let alpha = if hex.len() == 8 { channel(3) } else { Ok(255) };
+        match (channel(0), channel(1), channel(2), alpha) {
+            (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(HexColor(Rgba([r, g, b, a]))),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Rgba([r, g, b, a]) = self.0;
+        write!(f, "#{r:02X}{g:02X}{b:02X}")?;
+        if a != 255 {
+            write!(f, "{a:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 内置的调色板
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PalettePreset {
+    /// 深色主题为白色，浅色主题为深灰色
+    #[default]
+    Default,
+    /// 每个锁定键使用不同的鲜艳颜色
+    Vivid,
+    /// 半透明的白色与深灰色
+    Soft,
+}
+
+impl PalettePreset {
+    fn font_color(&self, theme: SystemTheme, key: LockKey) -> Rgba<u8> {
+        match (self, theme) {
+            (PalettePreset::Default, _) => theme.get_font_color(),
+            (PalettePreset::Vivid, SystemTheme::Dark) => match key {
+                LockKey::Caps => Rgba([79, 195, 247, 255]),
+                LockKey::Num => Rgba([129, 199, 132, 255]),
+                LockKey::Scroll => Rgba([255, 183, 77, 255]),
+            },
+            (PalettePreset::Vivid, SystemTheme::Light) => match key {
+                LockKey::Caps => Rgba([13, 71, 161, 255]),
+                LockKey::Num => Rgba([27, 94, 32, 255]),
+                LockKey::Scroll => Rgba([230, 81, 0, 255]),
+            },
+            (PalettePreset::Soft, SystemTheme::Dark) => Rgba([255, 255, 255, 184]),
+            (PalettePreset::Soft, SystemTheme::Light) => Rgba([31, 31, 31, 184]),
+        }
+    }
+}
+
+/// 一种主题下的自定义颜色，未设置的颜色使用调色板预设
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    /// 所有锁定键的颜色
+    pub font: Option<HexColor>,
+    /// 单个锁定键的颜色，优先于 `font`
+    pub caps: Option<HexColor>,
+    pub num: Option<HexColor>,
+    pub scroll: Option<HexColor>,
+}
+
+impl ThemeColors {
+    fn get(&self, key: LockKey) -> Option<HexColor> {
+        let color = match key {
+            LockKey::Caps => self.caps,
+            LockKey::Num => self.num,
+            LockKey::Scroll => self.scroll,
+        };
+        color.or(self.font)
+    }
+}
+
+/// 内置字形与文字标签的颜色，自定义图标与对比度模式不使用
+///
+/// `dark` 用于深色主题（深色背景），`light` 用于浅色主题（浅色背景）
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub preset: PalettePreset,
+    pub light: ThemeColors,
+    pub dark: ThemeColors,
+}
+
+impl Palette {
+    pub fn font_color(&self, theme: SystemTheme, key: LockKey) -> Rgba<u8> {
+        let custom = match theme {
+            SystemTheme::Light => &self.light,
+            SystemTheme::Dark => &self.dark,
+        };
+
+        custom
+            .get(key)
+            .map_or_else(|| self.preset.font_color(theme, key), |color| color.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_colours_override_the_preset_per_key() {
+        let palette: Palette = toml::from_str(
+            r##"
+            preset = "Vivid"
+
+            [dark]
+            font = "#FFEE5580"
+            scroll = "#ff0000"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            palette.font_color(SystemTheme::Dark, LockKey::Caps),
+            Rgba([255, 238, 85, 128])
+        );
+        assert_eq!(
+            palette.font_color(SystemTheme::Dark, LockKey::Scroll),
+            Rgba([255, 0, 0, 255])
+        );
+        assert_eq!(
+            palette.font_color(SystemTheme::Light, LockKey::Num),
+            Rgba([27, 94, 32, 255])
+        );
+        assert_eq!(
+            Palette::default().font_color(SystemTheme::Light, LockKey::Caps),
+            SystemTheme::Light.get_font_color()
+        );
+
+        let toml_str = toml::to_string(&palette).unwrap();
+        assert!(toml_str.contains(r##"font = "#FFEE5580""##));
+        assert!(toml_str.contains(r##"scroll = "#FF0000""##));
+    }
+
+    #[test]
+    fn invalid_colours_are_reported_at_their_key() {
+        for value in ["FFFFFF", "#FFF", "#GG0000", "#FFFFFFF", "#ÿÿÿ"] {
+            assert!(HexColor::try_from(value.to_owned()).is_err(), "{value}");
+        }
+
+        let error = toml::from_str::<Palette>(
+            r##"
+            [light]
+            num = "#12345"
+            "##,
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.contains("num = \"#12345\""), "{error}");
+        assert!(
+            error.contains("invalid colour '#12345', expected #RRGGBB or #RRGGBBAA"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! 不依赖系统的内存实现，用于测试核心逻辑
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    rc::Rc,
+    sync::mpsc::Sender,
+};
+
+use anyhow::{Result, anyhow};
+
+use super::{
+    AccentSource, FontLocator, InstanceLock, KeyHook, KeySource, MonitorArea, MonitorInfo,
+    MonitorProvider, Point, Rect, Registry, ScreenSampler,
+};
+use crate::key::KeyStates;
+use crate::monitor::MonitorId;
+
+/// 所有状态均可在测试中直接修改的平台实现
+#[derive(Debug, Clone)]
+pub struct FakePlatform {
+    pub key_states: Cell<KeyStates>,
+    /// 模拟键盘钩子安装失败
+    pub key_hook_fails: bool,
+    /// 钩子安装成功后保留的发送端，测试中可用它模拟锁定键事件
+    pub key_hook_sender: RefCell<Option<Sender<()>>>,
+    pub cursor: Cell<Point>,
+    pub caret: Cell<Option<Rect>>,
+    /// 已连接的显示器，包含 `(0, 0)` 的为主显示器
+    pub monitors: Vec<MonitorArea>,
+    /// 系统 DPI 的缩放比例
+    pub scale_factor: f64,
+    /// 与 `monitors` 一一对应的缩放比例，缺少时使用 `scale_factor`
+    pub monitor_scale_factors: Vec<f64>,
+    /// 屏幕上每个像素的颜色（RGB）
+    pub screen_color: Cell<[u8; 3]>,
+    /// 系统的强调色（RGB）
+    pub accent_color: Cell<Option<[u8; 3]>>,
+    pub registry: RefCell<HashMap<(String, String), FakeRegistryValue>>,
+    pub instances: Rc<RefCell<HashSet<String>>>,
+    /// 已安装的字体：名称 -> 文件路径
+    pub fonts: HashMap<String, PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FakeRegistryValue {
+    String(String),
+    Dword(u32),
+}
+
+impl Default for FakePlatform {
+    fn default() -> Self {
+        Self {
+            key_states: Cell::default(),
+            key_hook_fails: false,
+            key_hook_sender: RefCell::default(),
+            cursor: Cell::default(),
+            caret: Cell::default(),
+            monitors: vec![MonitorArea::new(Rect::new(0, 0, 1920, 1080))],
+            scale_factor: 1.0,
+            monitor_scale_factors: Vec::new(),
+            screen_color: Cell::new([0, 0, 0]),
+            accent_color: Cell::new(Some([0, 120, 215])),
+            registry: RefCell::default(),
+            instances: Rc::default(),
+            fonts: HashMap::new(),
+        }
+    }
+}
+
+impl FakePlatform {
+    pub fn with_monitors(monitors: Vec<Rect>) -> Self {
+        Self::with_monitor_areas(monitors.into_iter().map(MonitorArea::new).collect())
+    }
+
+    pub fn with_monitor_areas(monitors: Vec<MonitorArea>) -> Self {
+        Self {
+            monitors,
+            ..Default::default()
+        }
+    }
+
+    /// 距离 `point` 最近的显示器在 `monitors` 中的序号
+    fn nearest_monitor(&self, point: Point) -> Option<usize> {
+        let distance = |rect: &Rect| {
+            let dx = (rect.left - point.x).max(point.x - (rect.right - 1)).max(0) as i64;
+            let dy = (rect.top - point.y).max(point.y - (rect.bottom - 1)).max(0) as i64;
+            dx * dx + dy * dy
+        };
+
+        (0..self.monitors.len()).min_by_key(|index| distance(&self.monitors[*index].monitor))
+    }
+
+    pub fn set_dword(&self, path: &str, name: &str, value: u32) {
+        self.registry.borrow_mut().insert(
+            (path.to_owned(), name.to_owned()),
+            FakeRegistryValue::Dword(value),
+        );
+    }
+}
+
+impl KeySource for FakePlatform {
+    fn key_states(&self) -> KeyStates {
+        self.key_states.get()
+    }
+}
+
+impl KeyHook for FakePlatform {
+    /// 不运行消息循环，安装成功时立即返回
+    fn run_key_hook(&self, sender: Sender<()>) -> Result<()> {
+        if self.key_hook_fails {
+            return Err(anyhow!("Failed to install the keyboard hook"));
+        }
+
+        *self.key_hook_sender.borrow_mut() = Some(sender);
+        Ok(())
+    }
+
+    fn stop_key_hook(&self) {
+        self.key_hook_sender.borrow_mut().take();
+    }
+}
+
+impl MonitorProvider for FakePlatform {
+    fn cursor_position(&self) -> Result<Point> {
+        Ok(self.cursor.get())
+    }
+
+    fn monitor_area_from_point(&self, point: Point) -> Result<MonitorArea> {
+        self.nearest_monitor(point)
+            .map(|index| self.monitors[index])
+            .ok_or_else(|| anyhow!("No monitor connected"))
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn caret_rect(&self) -> Option<Rect> {
+        self.caret.get()
+    }
+
+    fn monitor_scale_factor(&self, point: Point) -> f64 {
+        self.nearest_monitor(point)
+            .and_then(|index| self.monitor_scale_factors.get(index).copied())
+            .unwrap_or(self.scale_factor)
+    }
+
+    /// 以显示器左上角坐标作为标识，移除其他显示器后保持不变
+    fn monitors(&self) -> Vec<MonitorInfo> {
+        self.monitors
+            .iter()
+            .map(|area| MonitorInfo {
+                id: MonitorId(format!("FAKE#{},{}", area.monitor.left, area.monitor.top)),
+                name: "Fake Monitor".to_owned(),
+                area: *area,
+                primary: area.monitor.contains(Point::new(0, 0)),
+            })
+            .collect()
+    }
+}
+
+impl ScreenSampler for FakePlatform {
+    fn capture(&self, rect: Rect) -> Option<Vec<u8>> {
+        if rect.width() <= 0 || rect.height() <= 0 {
+            return None;
+        }
+
+        let [r, g, b] = self.screen_color.get();
+        Some([b, g, r, 255].repeat((rect.width() * rect.height()) as usize))
+    }
+}
+
+impl AccentSource for FakePlatform {
+    fn accent_color(&self) -> Option<[u8; 3]> {
+        self.accent_color.get()
+    }
+}
+
+impl Registry for FakePlatform {
+    fn get_string(&self, path: &str, name: &str) -> Result<Option<String>> {
+        match self
+            .registry
+            .borrow()
+            .get(&(path.to_owned(), name.to_owned()))
+        {
+            Some(FakeRegistryValue::String(value)) => Ok(Some(value.clone())),
+            Some(FakeRegistryValue::Dword(_)) => Err(anyhow!("'{name}' is not a string value")),
+            None => Ok(None),
+        }
+    }
+
+    fn set_string(&self, path: &str, name: &str, value: &str) -> Result<()> {
+        self.registry.borrow_mut().insert(
+            (path.to_owned(), name.to_owned()),
+            FakeRegistryValue::String(value.to_owned()),
+        );
+        Ok(())
+    }
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<()> {
+        self.registry
+            .borrow_mut()
+            .remove(&(path.to_owned(), name.to_owned()))
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("'{name}' not found"))
+    }
+
+    fn get_dword(&self, path: &str, name: &str) -> Option<u32> {
+        match self
+            .registry
+            .borrow()
+            .get(&(path.to_owned(), name.to_owned()))
+        {
+            Some(FakeRegistryValue::Dword(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl FontLocator for FakePlatform {
+    fn find_font_family(&self, family: &str) -> Option<PathBuf> {
+        self.fonts.get(family).cloned()
+    }
+}
+
+pub struct FakeInstanceGuard {
+    name: String,
+    instances: Rc<RefCell<HashSet<String>>>,
+}
+
+impl InstanceLock for FakePlatform {
+    type Guard = FakeInstanceGuard;
+
+    fn acquire(&self, name: &str) -> Result<FakeInstanceGuard> {
+        if !self.instances.borrow_mut().insert(name.to_owned()) {
+            return Err(anyhow!("{name} already running"));
+        }
+
+        Ok(FakeInstanceGuard {
+            name: name.to_owned(),
+            instances: Rc::clone(&self.instances),
+        })
+    }
+}
+
+impl Drop for FakeInstanceGuard {
+    fn drop(&mut self) {
+        self.instances.borrow_mut().remove(&self.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_lock_is_released_on_drop() {
+        let platform = FakePlatform::default();
+
+        let guard = platform.acquire("CapsGlow").unwrap();
+        assert!(platform.acquire("CapsGlow").is_err());
+
+        drop(guard);
+        assert!(platform.acquire("CapsGlow").is_ok());
+    }
+}
@@ -0,0 +1,203 @@
+//! 平台抽象层
+//!
+//! 定位、主题、配置与按键状态等核心逻辑只依赖这里的 trait。
+//! Windows 实现见 `windows` 模块，`fake` 模块提供不依赖系统的内存实现，用于测试。
+
+#[cfg(test)]
+pub mod fake;
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "windows")]
+pub use self::windows::*;
+
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use anyhow::Result;
+
+use crate::key::KeyStates;
+use crate::monitor::MonitorId;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// 物理像素矩形，`right`、`bottom` 不包含在内（与 Win32 `RECT` 一致）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.left..self.right).contains(&point.x) && (self.top..self.bottom).contains(&point.y)
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.left + self.width() / 2, self.top + self.height() / 2)
+    }
+
+    /// 与 `other` 重叠的部分，不重叠时返回 `None`
+    pub fn intersection(&self, other: Rect) -> Option<Rect> {
+        let rect = Rect::new(
+            self.left.max(other.left),
+            self.top.max(other.top),
+            self.right.min(other.right),
+            self.bottom.min(other.bottom),
+        );
+        (rect.width() > 0 && rect.height() > 0).then_some(rect)
+    }
+}
+
+/// 显示器的物理矩形与工作区（除去任务栏等停靠栏后的区域）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorArea {
+    pub monitor: Rect,
+    pub work: Rect,
+}
+
+impl MonitorArea {
+    /// 工作区与显示器相同（没有停靠栏或任务栏自动隐藏）
+    pub fn new(monitor: Rect) -> Self {
+        Self {
+            monitor,
+            work: monitor,
+        }
+    }
+}
+
+/// 已连接的显示器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorInfo {
+    pub id: MonitorId,
+    /// 显示器的友好名称，如 `DELL U2419H`
+    pub name: String,
+    pub area: MonitorArea,
+    pub primary: bool,
+}
+
+/// 显示器相关的系统变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayChange {
+    /// 任务栏移动、调整大小或切换自动隐藏，工作区随之变化
+    WorkArea,
+    /// 显示器插拔、分辨率或方向变化，显示器的数量与位置可能都已改变
+    Topology,
+}
+
+/// 锁定键状态来源
+pub trait KeySource {
+    fn key_states(&self) -> KeyStates;
+}
+
+impl<T: KeySource + ?Sized> KeySource for &T {
+    fn key_states(&self) -> KeyStates {
+        (**self).key_states()
+    }
+}
+
+/// 锁定键事件的系统钩子
+pub trait KeyHook {
+    /// 安装钩子并运行消息循环，锁定键的按下与松开会通过 `sender` 通知
+    ///
+    /// 安装失败时立即返回错误并丢弃 `sender`，接收端随之断开，调用方据此回退到轮询
+    fn run_key_hook(&self, sender: Sender<()>) -> Result<()>;
+
+    /// 结束 `run_key_hook` 的消息循环并卸载钩子，发送端随之被丢弃；钩子未运行时不做任何事
+    fn stop_key_hook(&self);
+}
+
+/// 显示器几何信息
+pub trait MonitorProvider {
+    fn cursor_position(&self) -> Result<Point>;
+
+    /// 距离 `point` 最近的显示器的物理矩形与工作区
+    fn monitor_area_from_point(&self, point: Point) -> Result<MonitorArea>;
+
+    /// 系统 DPI 的缩放比例，与窗口所在的显示器无关，定位与窗口大小应使用 `monitor_scale_factor`
+    fn scale_factor(&self) -> f64;
+
+    /// 前台窗口中文字光标（插入符）的物理矩形，应用未使用系统光标时返回 `None`
+    fn caret_rect(&self) -> Option<Rect>;
+
+    /// 距离 `point` 最近的显示器的缩放比例（各显示器的 DPI 可能不同）
+    fn monitor_scale_factor(&self, point: Point) -> f64;
+
+    /// 当前连接的所有显示器，按系统枚举的顺序排列
+    fn monitors(&self) -> Vec<MonitorInfo>;
+}
+
+/// 屏幕截取
+pub trait ScreenSampler {
+    /// 截取屏幕上的矩形区域，按行返回 BGRA 像素（与 GDI 的顺序一致）
+    ///
+    /// 发布版本在 Windows 10 2004 及以上的系统中为指示器窗口设置 `WDA_EXCLUDEFROMCAPTURE`，
+    /// 截取结果中不包含指示器自身；其余情况下截取结果包含指示器，调用方只在指示器未显示时取样
+    fn capture(&self, rect: Rect) -> Option<Vec<u8>>;
+}
+
+/// 系统的强调色
+pub trait AccentSource {
+    /// DWM 的着色颜色 `[r, g, b]`（标题栏与窗口边框使用的强调色），无法获取时返回 `None`
+    fn accent_color(&self) -> Option<[u8; 3]>;
+}
+
+/// 当前用户（HKEY_CURRENT_USER）的注册表
+pub trait Registry {
+    fn get_string(&self, path: &str, name: &str) -> Result<Option<String>>;
+
+    fn set_string(&self, path: &str, name: &str, value: &str) -> Result<()>;
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<()>;
+
+    fn get_dword(&self, path: &str, name: &str) -> Option<u32>;
+}
+
+/// 系统已安装的字体
+pub trait FontLocator {
+    /// 按字体名称（如 `Segoe UI Emoji`）查找字体文件
+    fn find_font_family(&self, family: &str) -> Option<PathBuf>;
+}
+
+/// 不提供任何系统字体，只能使用文件路径指定的字体与内置字体
+impl FontLocator for () {
+    fn find_font_family(&self, _family: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// 单实例锁，`Guard` 释放时解除锁定
+pub trait InstanceLock {
+    type Guard;
+
+    fn acquire(&self, name: &str) -> Result<Self::Guard>;
+}
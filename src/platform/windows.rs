@@ -0,0 +1,664 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicU32, Ordering},
+        mpsc::Sender,
+    },
+};
+
+use anyhow::{Context, Result, anyhow};
+use windows::{
+    Win32::{
+        Foundation::{
+            CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE, HINSTANCE, HWND, LPARAM,
+            LRESULT, POINT, RECT, WPARAM,
+        },
+        Graphics::Dwm::DwmGetColorizationColor,
+        Graphics::Gdi::{
+            BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, ClientToScreen, CreateCompatibleBitmap,
+            CreateCompatibleDC, DIB_RGB_COLORS, DISPLAY_DEVICEW, DeleteDC, DeleteObject,
+            EnumDisplayDevicesW, EnumDisplayMonitors, GetDC, GetDIBits, GetDeviceCaps,
+            GetMonitorInfoW, HDC, HMONITOR, LOGPIXELSX, MONITOR_DEFAULTTONEAREST, MONITORINFO,
+            MONITORINFOEXW, MONITORINFOF_PRIMARY, MonitorFromPoint, ReleaseDC, SRCCOPY,
+            SelectObject,
+        },
+        System::{
+            LibraryLoader::GetModuleHandleW,
+            Registry::{HKEY_CURRENT_USER, REG_DWORD, RRF_RT_REG_DWORD, RegGetValueW},
+            Threading::{CreateMutexW, GetCurrentThreadId, ReleaseMutex},
+        },
+        UI::{
+            HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
+            Input::KeyboardAndMouse::GetKeyState,
+            Shell::{DefSubclassProc, SetWindowSubclass},
+            WindowsAndMessaging::{
+                CallNextHookEx, DBT_DEVNODES_CHANGED, EDD_GET_DEVICE_INTERFACE_NAME, GUITHREADINFO,
+                GetCursorPos, GetGUIThreadInfo, GetMessageW, HC_ACTION, KBDLLHOOKSTRUCT,
+                MB_ICONWARNING, MB_OK, MSG, MessageBoxW, PostThreadMessageW, SPI_SETWORKAREA,
+                SetWindowsHookExW, UnhookWindowsHookEx, WH_KEYBOARD_LL, WM_DEVICECHANGE,
+                WM_DISPLAYCHANGE, WM_DWMCOLORIZATIONCOLORCHANGED, WM_EXITSIZEMOVE, WM_QUIT,
+                WM_SETTINGCHANGE,
+            },
+        },
+    },
+    core::{BOOL, PCWSTR},
+};
+use winreg::{
+    RegKey,
+    enums::{HKEY_CURRENT_USER as HKCU, HKEY_LOCAL_MACHINE as HKLM, KEY_READ},
+    types::FromRegValue,
+};
+
+use super::{
+    AccentSource, DisplayChange, FontLocator, InstanceLock, KeyHook, KeySource, MonitorArea,
+    MonitorInfo, MonitorProvider, Point, Rect, Registry, ScreenSampler,
+};
+use crate::{
+    key::{KeyStates, LockKey},
+    monitor::MonitorId,
+    util::{from_wide, to_wide},
+};
+
+/// Win32 实现
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WindowsPlatform;
+
+impl KeySource for WindowsPlatform {
+    fn key_states(&self) -> KeyStates {
+        let mut states = KeyStates::default();
+        for key in LockKey::ALL {
+            let is_on = unsafe { (GetKeyState(key.virtual_key()) & 0x0001) != 0 };
+            states.set(key, is_on);
+        }
+        states
+    }
+}
+
+impl MonitorProvider for WindowsPlatform {
+    fn cursor_position(&self) -> Result<Point> {
+        let mut point = POINT::default();
+        unsafe { GetCursorPos(&mut point) }
+            .map_err(|e| anyhow!("Failed to get cursor position: {e}"))?;
+        Ok(Point::new(point.x, point.y))
+    }
+
+    fn monitor_area_from_point(&self, point: Point) -> Result<MonitorArea> {
+        unsafe {
+            let mut info: MONITORINFO = std::mem::zeroed();
+            info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+            let monitor = MonitorFromPoint(
+                POINT {
+                    x: point.x,
+                    y: point.y,
+                },
+                MONITOR_DEFAULTTONEAREST,
+            );
+
+            GetMonitorInfoW(monitor, &mut info).ok()?;
+
+            Ok(to_monitor_area(&info))
+        }
+    }
+
+    fn scale_factor(&self) -> f64 {
+        unsafe {
+            let hdc = GetDC(None);
+            let dpi = GetDeviceCaps(Some(hdc), LOGPIXELSX) as f64;
+            ReleaseDC(None, hdc);
+            dpi / 96.0
+        }
+    }
+
+    fn caret_rect(&self) -> Option<Rect> {
+        unsafe {
+            let mut info = GUITHREADINFO {
+                cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+                ..Default::default()
+            };
+            GetGUIThreadInfo(0, &mut info).ok()?;
+
+            if info.hwndCaret.is_invalid() {
+                return None;
+            }
+
+            // rcCaret 为光标所在窗口的客户区坐标
+            let caret = info.rcCaret;
+            let mut top_left = POINT {
+                x: caret.left,
+                y: caret.top,
+            };
+            let mut bottom_right = POINT {
+                x: caret.right,
+                y: caret.bottom,
+            };
+            if !ClientToScreen(info.hwndCaret, &mut top_left).as_bool()
+                || !ClientToScreen(info.hwndCaret, &mut bottom_right).as_bool()
+            {
+                return None;
+            }
+
+            Some(Rect::new(
+                top_left.x,
+                top_left.y,
+                bottom_right.x,
+                bottom_right.y,
+            ))
+        }
+    }
+
+    fn monitor_scale_factor(&self, point: Point) -> f64 {
+        unsafe {
+            let monitor = MonitorFromPoint(
+                POINT {
+                    x: point.x,
+                    y: point.y,
+                },
+                MONITOR_DEFAULTTONEAREST,
+            );
+
+            let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+            match GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) {
+                Ok(()) => dpi_x as f64 / 96.0,
+                Err(_) => self.scale_factor(),
+            }
+        }
+    }
+
+    fn monitors(&self) -> Vec<MonitorInfo> {
+        let mut handles: Vec<HMONITOR> = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                None,
+                None,
+                Some(collect_monitor),
+                LPARAM(&mut handles as *mut Vec<HMONITOR> as isize),
+            );
+        }
+
+        handles
+            .into_iter()
+            .filter_map(|monitor| unsafe {
+                let mut info: MONITORINFOEXW = std::mem::zeroed();
+                info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+                GetMonitorInfoW(monitor, &mut info.monitorInfo)
+                    .as_bool()
+                    .then_some(())?;
+
+                // 设备名（如 `\\.\DISPLAY1`）会随连接顺序变化，优先使用显示器的设备接口路径
+                let device_name = from_wide(&info.szDevice);
+                let mut device: DISPLAY_DEVICEW = std::mem::zeroed();
+                device.cb = std::mem::size_of::<DISPLAY_DEVICEW>() as u32;
+                let found = EnumDisplayDevicesW(
+                    PCWSTR(info.szDevice.as_ptr()),
+                    0,
+                    &mut device,
+                    EDD_GET_DEVICE_INTERFACE_NAME,
+                )
+                .as_bool();
+
+                let (id, name) = match found {
+                    true if device.DeviceID[0] != 0 => {
+                        (from_wide(&device.DeviceID), from_wide(&device.DeviceString))
+                    }
+                    _ => (device_name.clone(), device_name),
+                };
+
+                Some(MonitorInfo {
+                    id: MonitorId(id),
+                    name,
+                    area: to_monitor_area(&info.monitorInfo),
+                    primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+                })
+            })
+            .collect()
+    }
+}
+
+unsafe extern "system" fn collect_monitor(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let handles = unsafe { &mut *(data.0 as *mut Vec<HMONITOR>) };
+    handles.push(monitor);
+    true.into()
+}
+
+fn to_monitor_area(info: &MONITORINFO) -> MonitorArea {
+    let to_rect = |rect: RECT| Rect::new(rect.left, rect.top, rect.right, rect.bottom);
+    MonitorArea {
+        monitor: to_rect(info.rcMonitor),
+        work: to_rect(info.rcWork),
+    }
+}
+
+/// 支持 `WDA_EXCLUDEFROMCAPTURE` 的最低系统版本（Windows 10 2004）
+const EXCLUDE_FROM_CAPTURE_MIN_BUILD: u32 = 19041;
+
+/// 系统是否支持将窗口排除在屏幕截取之外（`WDA_EXCLUDEFROMCAPTURE`）
+///
+/// 更早的系统会退回到 `WDA_MONITOR`，受保护的窗口在截取结果中显示为黑色
+pub fn supports_exclude_from_capture() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        RegKey::predef(HKLM)
+            .open_subkey_with_flags(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion", KEY_READ)
+            .and_then(|key| key.get_value::<String, _>("CurrentBuildNumber"))
+            .ok()
+            .and_then(|build| build.parse::<u32>().ok())
+            .is_some_and(|build| build >= EXCLUDE_FROM_CAPTURE_MIN_BUILD)
+    })
+}
+
+impl ScreenSampler for WindowsPlatform {
+    fn capture(&self, rect: Rect) -> Option<Vec<u8>> {
+        let (width, height) = (rect.width(), rect.height());
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        unsafe {
+            let hdc_screen = GetDC(None);
+            let hdc_mem = CreateCompatibleDC(Some(hdc_screen));
+            let h_bitmap = CreateCompatibleBitmap(hdc_screen, width, height);
+            let _old_bitmap = SelectObject(hdc_mem, h_bitmap.into());
+
+            // Capture the target area
+            let captured = BitBlt(
+                hdc_mem,
+                0,
+                0,
+                width,
+                height,
+                Some(hdc_screen),
+                rect.left,
+                rect.top,
+                SRCCOPY,
+            )
+            .is_ok();
+
+            let mut buffer = vec![0u8; (width * height * 4) as usize];
+
+            if captured {
+                // Preparing the BITMAPINFO structure
+                let mut bmi = BITMAPINFO {
+                    bmiHeader: BITMAPINFOHEADER {
+                        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                        biWidth: width,
+                        biHeight: -height, // 从上到下排列
+                        biPlanes: 1,
+                        biBitCount: 32,
+                        biCompression: BI_RGB.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+
+                GetDIBits(
+                    hdc_mem,
+                    h_bitmap,
+                    0,
+                    height as u32,
+                    Some(buffer.as_mut_ptr() as *mut _),
+                    &mut bmi as *mut _ as *mut _,
+                    DIB_RGB_COLORS,
+                );
+            }
+
+            let _ = DeleteObject(h_bitmap.into());
+            let _ = DeleteDC(hdc_mem);
+            ReleaseDC(None, hdc_screen);
+
+            captured.then_some(buffer)
+        }
+    }
+}
+
+impl Registry for WindowsPlatform {
+    fn get_string(&self, path: &str, name: &str) -> Result<Option<String>> {
+        let key = RegKey::predef(HKCU)
+            .open_subkey_with_flags(path, KEY_READ)
+            .map_err(|e| anyhow!("Failed to open HKEY_CURRENT_USER\\{path} - {e}"))?;
+
+        match key.get_value::<String, _>(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed to get the registry value '{name}' - {e}")),
+        }
+    }
+
+    fn set_string(&self, path: &str, name: &str, value: &str) -> Result<()> {
+        let (key, _disp) = RegKey::predef(HKCU).create_subkey(path)?;
+        key.set_value(name, &value)
+            .with_context(|| format!("Failed to set the registry value '{name}'"))
+    }
+
+    fn delete_value(&self, path: &str, name: &str) -> Result<()> {
+        let (key, _disp) = RegKey::predef(HKCU).create_subkey(path)?;
+        key.delete_value(name)
+            .with_context(|| format!("Failed to delete the registry value '{name}'"))
+    }
+
+    fn get_dword(&self, path: &str, name: &str) -> Option<u32> {
+        let path = to_wide(path);
+        let name = to_wide(name);
+
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let mut reg_dword = REG_DWORD;
+
+        let ret = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(path.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                RRF_RT_REG_DWORD,
+                Some(&mut reg_dword),
+                Some(&mut value as *mut _ as *mut _),
+                Some(&mut size as *mut _),
+            )
+        };
+
+        ret.is_ok().then_some(value)
+    }
+}
+
+const FONTS_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Fonts";
+
+impl AccentSource for WindowsPlatform {
+    fn accent_color(&self) -> Option<[u8; 3]> {
+        let mut color = 0u32;
+        let mut opaque_blend = BOOL::default();
+        unsafe { DwmGetColorizationColor(&mut color, &mut opaque_blend) }.ok()?;
+
+        // 0xAARRGGBB，忽略透明度
+        let [_, r, g, b] = color.to_be_bytes();
+        Some([r, g, b])
+    }
+}
+
+impl FontLocator for WindowsPlatform {
+    /// 在系统（HKLM）与当前用户（HKCU）的字体注册表中查找，
+    /// 值名称形如 `Segoe UI Emoji (TrueType)` 或 `Cambria & Cambria Math (TrueType)`
+    fn find_font_family(&self, family: &str) -> Option<PathBuf> {
+        let fonts_dir = std::env::var_os("WINDIR")
+            .map_or_else(|| PathBuf::from(r"C:\Windows"), PathBuf::from)
+            .join("Fonts");
+
+        [HKLM, HKCU].into_iter().find_map(|hkey| {
+            let key = RegKey::predef(hkey)
+                .open_subkey_with_flags(FONTS_KEY, KEY_READ)
+                .ok()?;
+
+            key.enum_values().flatten().find_map(|(name, value)| {
+                let names = name
+                    .split_once(" (")
+                    .map_or(name.as_str(), |(names, _)| names);
+                names
+                    .split(" & ")
+                    .any(|name| name.trim().eq_ignore_ascii_case(family))
+                    .then(|| String::from_reg_value(&value).ok())
+                    .flatten()
+                    // 用户安装的字体记录的是完整路径，`join` 会直接使用它
+                    .map(|file| fonts_dir.join(file))
+            })
+        })
+    }
+}
+
+pub struct SingleInstance {
+    handle: Option<HANDLE>,
+}
+
+impl InstanceLock for WindowsPlatform {
+    type Guard = SingleInstance;
+
+    /// Creates a new system-wide mutex to ensure that only one instance of
+    /// the application is running.
+    fn acquire(&self, name: &str) -> Result<SingleInstance> {
+        let name = to_wide(name);
+
+        let handle = unsafe { CreateMutexW(None, false, PCWSTR(name.as_ptr())) }
+            .context("Failed to create single instance mutex.")?;
+
+        if handle.is_invalid() {
+            return Err(anyhow!(
+                "Failed to create single instance mutex: {:?}",
+                unsafe { GetLastError() }
+            ));
+        }
+
+        let handle = if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            return Err(anyhow!(
+                "CapsGlow already running, exit the new process: {:?}",
+                unsafe { GetLastError() }
+            ));
+        } else {
+            Some(handle)
+        };
+
+        Ok(SingleInstance { handle })
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(handle) = self.handle {
+                let _ = ReleaseMutex(handle);
+                let _ = CloseHandle(handle);
+            }
+        }
+    }
+}
+
+/// 钩子安装成功后才保存发送端，钩子卸载时取出，接收端随之断开
+static HOOK_SENDER: Mutex<Option<Sender<()>>> = Mutex::new(None);
+/// 运行钩子消息循环的线程，钩子未运行时为 0
+static HOOK_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+
+unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+        if LockKey::ALL
+            .iter()
+            .any(|key| key.virtual_key() as u32 == info.vkCode)
+            && let Some(sender) = HOOK_SENDER.lock().unwrap().as_ref()
+        {
+            let _ = sender.send(());
+        }
+    }
+
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+impl KeyHook for WindowsPlatform {
+    /// 在当前线程安装低级键盘钩子（WH_KEYBOARD_LL）
+    ///
+    /// 该函数会一直阻塞，直到消息循环结束
+    fn run_key_hook(&self, sender: Sender<()>) -> Result<()> {
+        run_keyboard_hook(sender)
+    }
+
+    /// 向钩子线程发送 `WM_QUIT`，使其退出消息循环并卸载钩子
+    fn stop_key_hook(&self) {
+        let thread_id = HOOK_THREAD_ID.load(Ordering::Acquire);
+        if thread_id != 0 {
+            let _ = unsafe { PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+        }
+    }
+}
+
+fn run_keyboard_hook(sender: Sender<()>) -> Result<()> {
+    if HOOK_SENDER.lock().unwrap().is_some() {
+        return Err(anyhow!("The keyboard hook is already running"));
+    }
+
+    unsafe {
+        let module =
+            GetModuleHandleW(None).map_err(|e| anyhow!("Failed to get the module handle - {e}"))?;
+
+        let hook = SetWindowsHookExW(
+            WH_KEYBOARD_LL,
+            Some(keyboard_hook_proc),
+            Some(HINSTANCE(module.0)),
+            0,
+        )
+        .map_err(|e| anyhow!("Failed to install the keyboard hook - {e}"))?;
+        *HOOK_SENDER.lock().unwrap() = Some(sender);
+        HOOK_THREAD_ID.store(GetCurrentThreadId(), Ordering::Release);
+
+        // 低级键盘钩子需要安装线程持续处理消息，`stop_key_hook` 发送 `WM_QUIT` 后结束
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+
+        HOOK_THREAD_ID.store(0, Ordering::Release);
+        let _ = UnhookWindowsHookEx(hook);
+        HOOK_SENDER.lock().unwrap().take();
+    }
+
+    Ok(())
+}
+
+static DISPLAY_CHANGE_CALLBACK: OnceLock<Box<dyn Fn(DisplayChange) + Send + Sync>> =
+    OnceLock::new();
+
+unsafe extern "system" fn display_change_subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    // 分辨率、方向或显示器数量变化时系统广播 `WM_DISPLAYCHANGE`，
+    // 插拔显示器时还会广播设备树变化的 `WM_DEVICECHANGE`
+    let change = match msg {
+        WM_SETTINGCHANGE if wparam.0 as u32 == SPI_SETWORKAREA.0 => Some(DisplayChange::WorkArea),
+        WM_DISPLAYCHANGE => Some(DisplayChange::Topology),
+        WM_DEVICECHANGE if wparam.0 as u32 == DBT_DEVNODES_CHANGED => Some(DisplayChange::Topology),
+        _ => None,
+    };
+
+    if let Some(change) = change
+        && let Some(callback) = DISPLAY_CHANGE_CALLBACK.get()
+    {
+        callback(change);
+    }
+
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+/// 子类化窗口 `hwnd`，任务栏移动、调整大小、切换自动隐藏，或显示器插拔、分辨率与方向变化时调用 `callback`
+pub fn watch_display_changes(
+    hwnd: isize,
+    callback: impl Fn(DisplayChange) + Send + Sync + 'static,
+) -> Result<()> {
+    DISPLAY_CHANGE_CALLBACK
+        .set(Box::new(callback))
+        .map_err(|_| anyhow!("The display changes are already being watched"))?;
+
+    unsafe {
+        SetWindowSubclass(
+            HWND(hwnd as *mut _),
+            Some(display_change_subclass_proc),
+            1,
+            0,
+        )
+    }
+    .ok()
+    .context("Failed to subclass the indicator window")
+}
+
+static DRAG_END_CALLBACK: OnceLock<Box<dyn Fn() + Send + Sync>> = OnceLock::new();
+
+unsafe extern "system" fn drag_end_subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    // 拖动窗口时系统进入移动窗口的模态循环，松开鼠标后退出循环
+    if msg == WM_EXITSIZEMOVE
+        && let Some(callback) = DRAG_END_CALLBACK.get()
+    {
+        callback();
+    }
+
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+/// 子类化窗口 `hwnd`，用户拖动窗口后松开鼠标时调用 `callback`
+pub fn watch_drag_end(hwnd: isize, callback: impl Fn() + Send + Sync + 'static) -> Result<()> {
+    DRAG_END_CALLBACK
+        .set(Box::new(callback))
+        .map_err(|_| anyhow!("The window dragging is already being watched"))?;
+
+    unsafe { SetWindowSubclass(HWND(hwnd as *mut _), Some(drag_end_subclass_proc), 2, 0) }
+        .ok()
+        .context("Failed to subclass the indicator window")
+}
+
+static THEME_CHANGE_CALLBACK: OnceLock<Box<dyn Fn() + Send + Sync>> = OnceLock::new();
+
+unsafe extern "system" fn theme_change_subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    // 切换深浅色模式或强调色时系统广播 `lParam` 为 `ImmersiveColorSet` 的 `WM_SETTINGCHANGE`，
+    // DWM 着色颜色变化时还会广播 `WM_DWMCOLORIZATIONCOLORCHANGED`
+    let changed = match msg {
+        WM_SETTINGCHANGE => is_immersive_color_set(lparam),
+        WM_DWMCOLORIZATIONCOLORCHANGED => true,
+        _ => false,
+    };
+
+    if changed && let Some(callback) = THEME_CHANGE_CALLBACK.get() {
+        callback();
+    }
+
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+/// `WM_SETTINGCHANGE` 的 `lParam` 是否为 `ImmersiveColorSet`
+fn is_immersive_color_set(lparam: LPARAM) -> bool {
+    if lparam.0 == 0 {
+        return false;
+    }
+
+    unsafe { PCWSTR(lparam.0 as *const u16).to_string() }
+        .is_ok_and(|setting| setting == "ImmersiveColorSet")
+}
+
+/// 子类化窗口 `hwnd`，用户切换深浅色模式或强调色时调用 `callback`
+pub fn watch_theme_changes(hwnd: isize, callback: impl Fn() + Send + Sync + 'static) -> Result<()> {
+    THEME_CHANGE_CALLBACK
+        .set(Box::new(callback))
+        .map_err(|_| anyhow!("The theme changes are already being watched"))?;
+
+    unsafe { SetWindowSubclass(HWND(hwnd as *mut _), Some(theme_change_subclass_proc), 3, 0) }
+        .ok()
+        .context("Failed to subclass the indicator window")
+}
+
+/// 显示带警告图标的消息框，该函数会阻塞到消息框关闭
+pub fn show_warning_message(title: &str, message: &str) {
+    let title = to_wide(title);
+    let message = to_wide(message);
+    unsafe {
+        MessageBoxW(
+            None,
+            PCWSTR(message.as_ptr()),
+            PCWSTR(title.as_ptr()),
+            MB_OK | MB_ICONWARNING,
+        );
+    }
+}
@@ -0,0 +1,776 @@
+use std::collections::HashMap;
+
+use ab_glyph::{Font, OutlinedGlyph, PxScale, Rect as GlyphRect, ScaleFont, point};
+use anyhow::{Result, anyhow};
+use image::{ImageBuffer, Rgba, RgbaImage, imageops::FilterType};
+
+use crate::{
+    contrast::contrasting_extreme,
+    font::{FontChain, FontSetting, is_variation_selector},
+    icon::CustomIcon,
+    key::{LockKey, WatchedKeys, layout_indicators},
+    osd::Indicator,
+    palette::Palette,
+    platform::FontLocator,
+    style::{GlyphEffect, IndicatorStyle, StyleKind},
+    theme::{IndicatorColors, SystemTheme},
+};
+
+/// 字形缓存的上限，对比度模式下颜色随背景连续变化，超过上限时清空缓存
+const GLYPH_CACHE_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GlyphCacheKey {
+    glyph: String,
+    kind: StyleKind,
+    /// 前景、背景与效果的颜色
+    colors: [[u8; 4]; 3],
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct IconCacheKey {
+    key: LockKey,
+    theme: SystemTheme,
+    width: u32,
+    height: u32,
+}
+
+/// 指示器渲染器
+///
+/// 字体只在首次绘制时读取并解析，光栅化后的字形按 `(文字, 颜色, 尺寸)` 缓存，
+/// 重新采样后的自定义图标按 `(主题, 尺寸)` 缓存，之后的重绘只需把缓存的像素复制到缓冲区
+pub struct IndicatorRenderer {
+    custom_icons: HashMap<LockKey, CustomIcon>,
+    fonts: FontChain,
+    /// 文字标签使用的字体，随样式中的字体与字重变化重新创建
+    text_fonts: Option<FontChain>,
+    locator: Box<dyn FontLocator>,
+    style: IndicatorStyle,
+    palette: Palette,
+    glyph_cache: HashMap<GlyphCacheKey, Vec<u32>>,
+    icon_cache: HashMap<IconCacheKey, (RgbaImage, (u32, u32))>,
+}
+
+impl IndicatorRenderer {
+    pub fn new(
+        custom_icons: HashMap<LockKey, CustomIcon>,
+        font_setting: &FontSetting,
+        locator: impl FontLocator + 'static,
+    ) -> Self {
+        Self {
+            custom_icons,
+            fonts: FontChain::new(font_setting, &locator),
+            text_fonts: None,
+            locator: Box::new(locator),
+            style: IndicatorStyle::default(),
+            palette: Palette::default(),
+            glyph_cache: HashMap::new(),
+            icon_cache: HashMap::new(),
+        }
+    }
+
+    pub fn custom_icons(&self) -> &HashMap<LockKey, CustomIcon> {
+        &self.custom_icons
+    }
+
+    /// 更新指示器样式，样式变化时清空字形缓存
+    pub fn set_style(&mut self, style: &IndicatorStyle) {
+        if *style == self.style {
+            return;
+        }
+
+        if style.text_font_setting() != self.style.text_font_setting() {
+            self.text_fonts = None;
+        }
+        self.style = style.clone();
+        self.glyph_cache.clear();
+    }
+
+    /// 更新调色板，字形缓存按颜色区分，无需清空
+    pub fn set_palette(&mut self, palette: &Palette) {
+        if *palette != self.palette {
+            self.palette = palette.clone();
+        }
+    }
+
+    /// 将指示器绘制到 `width × height` 的缓冲区，即指示器窗口实际显示的内容
+    ///
+    /// OSD 指示器总是以文字标签绘制，如 `Caps Lock ON`
+    pub fn render(
+        &mut self,
+        buffer: &mut [u32],
+        width: u32,
+        height: u32,
+        indicators: &[Indicator],
+        colors: IndicatorColors,
+        watched_keys: &WatchedKeys,
+    ) {
+        buffer.fill(0);
+
+        let cells = layout_indicators(width, indicators.len());
+
+        for (indicator, (cell_x, cell_width)) in indicators.iter().zip(cells) {
+            let key = match *indicator {
+                Indicator::Key(key) => key,
+                Indicator::Toggle(key, on) => {
+                    let text = format!("{} {}", key.name(), if on { "ON" } else { "OFF" });
+                    let glyph =
+                        self.get_glyph(key, &text, StyleKind::Text, colors, cell_width, height);
+
+                    blit(buffer, width, glyph, cell_x, cell_width, height);
+                    continue;
+                }
+            };
+
+            if let Some((icon_buffer, icon_size)) =
+                self.get_icon(key, colors.theme, cell_width, height)
+            {
+                render_icon_to_buffer(
+                    buffer,
+                    width,
+                    icon_buffer,
+                    *icon_size,
+                    cell_x,
+                    cell_width,
+                    height,
+                );
+            } else {
+                let setting = watched_keys.get(key);
+                let kind = self.style.kind;
+                let glyph = match kind {
+                    StyleKind::Glyph => setting.glyph.as_str(),
+                    StyleKind::Text if setting.label.is_empty() => key.default_label(),
+                    StyleKind::Text => setting.label.as_str(),
+                };
+                let glyph = self.get_glyph(key, glyph, kind, colors, cell_width, height);
+
+                blit(buffer, width, glyph, cell_x, cell_width, height);
+            }
+        }
+    }
+
+    /// 重新采样到 `width × height` 的自定义图标，未提供自定义图标时返回 `None`
+    ///
+    /// 尺寸变化（如窗口大小或缩放比例变化）时清空缓存
+    fn get_icon(
+        &mut self,
+        key: LockKey,
+        theme: SystemTheme,
+        width: u32,
+        height: u32,
+    ) -> Option<&(RgbaImage, (u32, u32))> {
+        let custom_icon = self.custom_icons.get(&key)?;
+        let cache_key = IconCacheKey {
+            key,
+            theme,
+            width,
+            height,
+        };
+
+        if !self.icon_cache.contains_key(&cache_key) {
+            if self
+                .icon_cache
+                .keys()
+                .any(|cached| (cached.width, cached.height) != (width, height))
+            {
+                self.icon_cache.clear();
+            }
+
+            let icon = custom_icon.get_resized_icon_data_and_size(theme, width, height);
+            self.icon_cache.insert(cache_key, icon);
+        }
+
+        self.icon_cache.get(&cache_key)
+    }
+
+    /// 光栅化失败时改用内置字体绘制回退字符，因此总能得到结果
+    fn get_glyph(
+        &mut self,
+        key: LockKey,
+        glyph: &str,
+        kind: StyleKind,
+        colors: IndicatorColors,
+        width: u32,
+        height: u32,
+    ) -> &[u32] {
+        let color = colors
+            .font
+            .unwrap_or_else(|| self.palette.font_color(colors.theme, key));
+        let effect_color = {
+            let [r, g, b] = contrasting_extreme([color[0], color[1], color[2]]);
+            Rgba([r, g, b, color[3]])
+        };
+        let cache_key = GlyphCacheKey {
+            glyph: glyph.to_owned(),
+            kind,
+            colors: [color.0, colors.plate.0, effect_color.0],
+            width,
+            height,
+        };
+
+        if !self.glyph_cache.contains_key(&cache_key) {
+            if self.glyph_cache.len() >= GLYPH_CACHE_CAPACITY {
+                self.glyph_cache.clear();
+            }
+
+            let style = &self.style;
+            let pixels = match kind {
+                StyleKind::Glyph => {
+                    let (font, text) = self.fonts.resolve(key, glyph);
+                    rasterize_text(font, &text, color, width, height)
+                }
+                StyleKind::Text => {
+                    let plate_color = style.background_plate.then_some(colors.plate);
+                    let (font, text) = self
+                        .text_fonts
+                        .get_or_insert_with(|| {
+                            FontChain::new(&style.text_font_setting(), &*self.locator)
+                        })
+                        .resolve(key, glyph);
+                    rasterize_label(font, &text, color, plate_color, width, height, style)
+                }
+            }
+            .unwrap_or_else(|e| {
+                log::warn!("{e:#}, use the bundled font instead");
+                let fallback = key.fallback_glyph().to_string();
+                rasterize_text(self.fonts.bundled(), &fallback, color, width, height)
+                    .unwrap_or_else(|_| vec![0; (width * height) as usize])
+            });
+
+            let has_plate = kind == StyleKind::Text && style.background_plate;
+            let pixels = if has_plate {
+                pixels
+            } else {
+                apply_glyph_effect(&pixels, width, height, style.effect, effect_color)
+            };
+            self.glyph_cache.insert(cache_key.clone(), pixels);
+        }
+
+        &self.glyph_cache[&cache_key]
+    }
+}
+
+/// 将 `pixels`（`area_width × height`）逐行复制到 `buffer` 的 `[area_x, area_x + area_width)` 区域
+fn blit(
+    buffer: &mut [u32],
+    stride: u32,
+    pixels: &[u32],
+    area_x: u32,
+    area_width: u32,
+    height: u32,
+) {
+    for y in 0..height as usize {
+        let src = &pixels[y * area_width as usize..][..area_width as usize];
+        let start = y * stride as usize + area_x as usize;
+        if let Some(dst) = buffer.get_mut(start..start + area_width as usize) {
+            dst.copy_from_slice(src);
+        }
+    }
+}
+
+/// 在 `width × height` 的区域内居中光栅化一行文字，返回按行存储的 `0xAARRGGBB` 预乘像素
+///
+/// 文字按字体的步进与字距排成一行，再整体等比缩放到恰好放入区域
+pub fn rasterize_text(
+    font: &impl Font,
+    text: &str,
+    color: Rgba<u8>,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u32>> {
+    let mut buffer = vec![0u32; (width * height) as usize];
+
+    let base_scale = PxScale::from(100.0); // 任意较大的基准值
+    let bounds = text_bounds(&outline_text(font, text, base_scale, 0.0))
+        .ok_or_else(|| anyhow!("Failed to outline text '{text}'"))?;
+
+    let factor = f32::min(
+        width as f32 / bounds.width(),
+        height as f32 / bounds.height(),
+    );
+
+    let outlines = outline_text(font, text, scale_by(base_scale, factor), 0.0);
+    let final_bounds =
+        text_bounds(&outlines).ok_or_else(|| anyhow!("Failed to outline text '{text}'"))?;
+
+    draw_outlines(
+        &mut buffer,
+        width,
+        height,
+        &outlines,
+        center_offset(final_bounds, width, height),
+        color,
+    );
+
+    Ok(buffer)
+}
+
+/// 按样式在 `width × height` 的区域内居中光栅化文字标签，可选绘制圆角背景
+///
+/// 字号由 `style.size` 决定，文字（含背景）放不下时整体缩小
+pub fn rasterize_label(
+    font: &impl Font,
+    text: &str,
+    color: Rgba<u8>,
+    plate_color: Option<Rgba<u8>>,
+    width: u32,
+    height: u32,
+    style: &IndicatorStyle,
+) -> Result<Vec<u32>> {
+    let mut buffer = vec![0u32; (width * height) as usize];
+
+    let font_size = (style.size * height as f32).max(1.0);
+    let base_scale = PxScale::from(font_size);
+    let bounds = text_bounds(&outline_text(
+        font,
+        text,
+        base_scale,
+        style.letter_spacing * font_size,
+    ))
+    .ok_or_else(|| anyhow!("Failed to outline text '{text}'"))?;
+
+    // 背景比文字四周多出的边距，按字号计算
+    let (padding_x, padding_y) = match plate_color {
+        Some(_) => (0.4 * font_size, 0.25 * font_size),
+        None => (0.0, 0.0),
+    };
+
+    let factor = f32::min(
+        1.0,
+        f32::min(
+            width as f32 / (bounds.width() + 2.0 * padding_x),
+            height as f32 / (bounds.height() + 2.0 * padding_y),
+        ),
+    );
+    let font_size = font_size * factor;
+
+    let outlines = outline_text(
+        font,
+        text,
+        scale_by(base_scale, factor),
+        style.letter_spacing * font_size,
+    );
+    let final_bounds =
+        text_bounds(&outlines).ok_or_else(|| anyhow!("Failed to outline text '{text}'"))?;
+    let offset = center_offset(final_bounds, width, height);
+
+    if let Some(plate_color) = plate_color {
+        let plate = GlyphRect {
+            min: point(
+                final_bounds.min.x + offset.0 - padding_x * factor,
+                final_bounds.min.y + offset.1 - padding_y * factor,
+            ),
+            max: point(
+                final_bounds.max.x + offset.0 + padding_x * factor,
+                final_bounds.max.y + offset.1 + padding_y * factor,
+            ),
+        };
+        let radius = style.plate_radius.clamp(0.0, 0.5) * plate.height();
+        fill_rounded_rect(&mut buffer, width, height, plate, radius, plate_color);
+    }
+
+    draw_outlines(&mut buffer, width, height, &outlines, offset, color);
+
+    Ok(buffer)
+}
+
+fn scale_by(scale: PxScale, factor: f32) -> PxScale {
+    PxScale {
+        x: scale.x * factor,
+        y: scale.y * factor,
+    }
+}
+
+/// 按步进、字距与额外字间距 `spacing` 排列文字，返回每个有轮廓的字形（空格等没有轮廓）
+fn outline_text(font: &impl Font, text: &str, scale: PxScale, spacing: f32) -> Vec<OutlinedGlyph> {
+    let scaled = font.as_scaled(scale);
+    let mut caret = 0.0;
+    let mut previous = None;
+
+    text.chars()
+        .filter(|c| !is_variation_selector(*c))
+        .filter_map(|c| {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id) + spacing;
+            }
+            previous = Some(id);
+
+            let glyph = id.with_scale_and_position(scale, point(caret, 0.0));
+            caret += scaled.h_advance(id);
+            font.outline_glyph(glyph)
+        })
+        .collect()
+}
+
+/// 整行文字的像素包围盒
+fn text_bounds(outlines: &[OutlinedGlyph]) -> Option<GlyphRect> {
+    outlines
+        .iter()
+        .map(|outlined| outlined.px_bounds())
+        .reduce(|a, b| GlyphRect {
+            min: point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+            max: point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+        })
+}
+
+/// 将包围盒移到 `width × height` 区域中央所需的偏移
+fn center_offset(bounds: GlyphRect, width: u32, height: u32) -> (f32, f32) {
+    (
+        (width as f32 - bounds.width()) / 2.0 - bounds.min.x,
+        (height as f32 - bounds.height()) / 2.0 - bounds.min.y,
+    )
+}
+
+/// 以 `color` 按覆盖率叠加（source-over）到预乘缓冲区
+fn blend(buffer: &mut [u32], idx: usize, color: Rgba<u8>, coverage: f32) {
+    let out_a = coverage.clamp(0.0, 1.0) * color[3] as f32 / 255.0;
+    if out_a <= 0.0 {
+        return;
+    }
+
+    let dst = buffer[idx];
+    let inv = 1.0 - out_a;
+    let channel = |src: u8, shift: u32| {
+        let dst = ((dst >> shift) & 0xFF) as f32;
+        ((src as f32 * out_a + dst * inv).round() as u32).min(255) << shift
+    };
+
+    buffer[idx] =
+        channel(255, 24) | channel(color[0], 16) | channel(color[1], 8) | channel(color[2], 0);
+}
+
+fn draw_outlines(
+    buffer: &mut [u32],
+    width: u32,
+    height: u32,
+    outlines: &[OutlinedGlyph],
+    offset: (f32, f32),
+    color: Rgba<u8>,
+) {
+    for outlined in outlines {
+        let glyph_bounds = outlined.px_bounds();
+        let start_x = (glyph_bounds.min.x + offset.0) as i32;
+        let start_y = (glyph_bounds.min.y + offset.1) as i32;
+
+        outlined.draw(|x, y, coverage| {
+            let screen_x = start_x + x as i32;
+            let screen_y = start_y + y as i32;
+
+            if screen_x < 0 || screen_x >= width as i32 || screen_y < 0 || screen_y >= height as i32
+            {
+                return;
+            }
+
+            blend(
+                buffer,
+                (screen_y as u32 * width + screen_x as u32) as usize,
+                color,
+                coverage,
+            );
+        });
+    }
+}
+
+/// 在预乘像素 `pixels` 的下方按覆盖率绘制 `effect`，返回合成后的像素
+///
+/// 描边宽度与阴影偏移随区域高度缩放
+pub fn apply_glyph_effect(
+    pixels: &[u32],
+    width: u32,
+    height: u32,
+    effect: GlyphEffect,
+    color: Rgba<u8>,
+) -> Vec<u32> {
+    let (width, height) = (width as i32, height as i32);
+    let alpha = |x: i32, y: i32| {
+        if x < 0 || x >= width || y < 0 || y >= height {
+            0
+        } else {
+            pixels[(y * width + x) as usize] >> 24
+        }
+    };
+
+    let coverage: Box<dyn Fn(i32, i32) -> f32> = match effect {
+        GlyphEffect::None => return pixels.to_vec(),
+        // 取圆形邻域内的最大覆盖率，使字形向外扩展
+        GlyphEffect::Outline => {
+            let radius = (height / 64).max(1);
+            Box::new(move |x, y| {
+                let mut max = 0;
+                for dy in -radius..=radius {
+                    for dx in -radius..=radius {
+                        if dx * dx + dy * dy <= radius * radius {
+                            max = max.max(alpha(x + dx, y + dy));
+                        }
+                    }
+                }
+                max as f32 / 255.0
+            })
+        }
+        GlyphEffect::Shadow => {
+            let offset = (height / 48).max(1);
+            Box::new(move |x, y| alpha(x - offset, y - offset) as f32 / 255.0 * 0.6)
+        }
+    };
+
+    let mut buffer = vec![0u32; pixels.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let idx = (y * width + x) as usize;
+            blend(&mut buffer, idx, color, coverage(x, y));
+
+            // 字形（预乘）叠加在效果之上
+            let src = pixels[idx];
+            let inv = 255 - (src >> 24);
+            let dst = buffer[idx];
+            let channel = |shift: u32| {
+                let value = ((src >> shift) & 0xFF) + (((dst >> shift) & 0xFF) * inv + 127) / 255;
+                value.min(255) << shift
+            };
+            buffer[idx] = channel(24) | channel(16) | channel(8) | channel(0);
+        }
+    }
+
+    buffer
+}
+
+/// 绘制抗锯齿的圆角矩形
+fn fill_rounded_rect(
+    buffer: &mut [u32],
+    width: u32,
+    height: u32,
+    rect: GlyphRect,
+    radius: f32,
+    color: Rgba<u8>,
+) {
+    let x_range =
+        (rect.min.x.floor().max(0.0) as u32)..(rect.max.x.ceil().min(width as f32) as u32);
+    let y_range =
+        (rect.min.y.floor().max(0.0) as u32)..(rect.max.y.ceil().min(height as f32) as u32);
+
+    for y in y_range {
+        for x in x_range.clone() {
+            // 像素中心到圆角矩形边缘的有符号距离，内部为负
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let dx = (rect.min.x + radius - px)
+                .max(px - (rect.max.x - radius))
+                .max(0.0);
+            let dy = (rect.min.y + radius - py)
+                .max(py - (rect.max.y - radius))
+                .max(0.0);
+            let outside_corner = (dx * dx + dy * dy).sqrt() - radius;
+            let outside_edge = (rect.min.x - px)
+                .max(px - rect.max.x)
+                .max(rect.min.y - py)
+                .max(py - rect.max.y);
+            let distance = outside_corner.max(outside_edge);
+
+            blend(buffer, (y * width + x) as usize, color, 0.5 - distance);
+        }
+    }
+}
+
+/// 在 `buffer` 的 `[area_x, area_x + area_width) × [0, height)` 区域内居中绘制图标，图标过大时等比缩小
+pub fn render_icon_to_buffer(
+    buffer: &mut [u32],
+    stride: u32,
+    icon_buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    icon_size: (u32, u32),
+    area_x: u32,
+    area_width: u32,
+    height: u32,
+) {
+    // 多个指示器共用窗口时，图标可能比格子大，等比缩小到格子内
+    let resized;
+    let (icon_buffer, icon_size) = if icon_size.0 > area_width || icon_size.1 > height {
+        let factor = f64::min(
+            area_width as f64 / icon_size.0 as f64,
+            height as f64 / icon_size.1 as f64,
+        );
+        let width = ((icon_size.0 as f64 * factor).round() as u32).max(1);
+        let height = ((icon_size.1 as f64 * factor).round() as u32).max(1);
+        resized = image::imageops::resize(icon_buffer, width, height, FilterType::Lanczos3);
+        (&resized, (width, height))
+    } else {
+        (icon_buffer, icon_size)
+    };
+
+    // 计算居中位置
+    let start_x = area_x + (area_width.saturating_sub(icon_size.0)) / 2;
+    let start_y = (height.saturating_sub(icon_size.1)) / 2;
+
+    // 确保图标不会超出窗口边界
+    let render_width = icon_size
+        .0
+        .min((area_x + area_width).saturating_sub(start_x));
+    let render_height = icon_size.1.min(height.saturating_sub(start_y));
+
+    // 遍历需要渲染的每个像素
+    for y in 0..render_height {
+        for x in 0..render_width {
+            let pixel = icon_buffer.get_pixel(x, y).0;
+            let a = pixel[3] as u32;
+
+            // Alpha blending fix：预乘
+            let alpha_f = a as f32 / 255.0;
+            let r = (pixel[0] as f32 * alpha_f).round() as u32;
+            let g = (pixel[1] as f32 * alpha_f).round() as u32;
+            let b = (pixel[2] as f32 * alpha_f).round() as u32;
+
+            let dst_x = start_x + x;
+            let dst_y = start_y + y;
+
+            // 修正后的索引计算：y * 宽度 + x
+            let idx = (dst_y * stride + dst_x) as usize;
+
+            // 写入 buffer
+            if idx < buffer.len() {
+                buffer[idx] = (a << 24) | (r << 16) | (g << 8) | b;
+            }
+        }
+    }
+}
+
+/// 将 `0xAARRGGBB` 预乘像素转换为普通（非预乘）RGBA 图像
+pub fn buffer_to_rgba_image(buffer: &[u32], width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = buffer[(y * width + x) as usize];
+        let a = (pixel >> 24) & 0xFF;
+        let unpremultiply = |c: u32| {
+            (c * 255 + a / 2)
+                .checked_div(a)
+                .map_or(0, |c| c.min(255) as u8)
+        };
+
+        Rgba([
+            unpremultiply((pixel >> 16) & 0xFF),
+            unpremultiply((pixel >> 8) & 0xFF),
+            unpremultiply(pixel & 0xFF),
+            a as u8,
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icon_is_centered_and_premultiplied() {
+        let icon = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 128]));
+        let mut buffer = vec![0u32; 4 * 4];
+
+        render_icon_to_buffer(&mut buffer, 4, &icon, (2, 2), 0, 4, 4);
+
+        let red = (128 << 24) | (128 << 16);
+        #[rustfmt::skip]
+        assert_eq!(buffer, vec![
+            0, 0, 0, 0,
+            0, red, red, 0,
+            0, red, red, 0,
+            0, 0, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn oversized_icon_is_scaled_into_its_cell() {
+        let icon = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]));
+        let mut buffer = vec![0u32; 8 * 4];
+
+        render_icon_to_buffer(&mut buffer, 8, &icon, (8, 8), 4, 4, 4);
+
+        for y in 0..4 {
+            let row = &buffer[y * 8..(y + 1) * 8];
+            assert!(row[..4].iter().all(|p| *p == 0));
+            assert!(row[4..].iter().all(|p| *p == 0xFF0000FF));
+        }
+    }
+
+    #[test]
+    fn text_is_scaled_to_fill_the_area() {
+        let fonts = FontChain::new(&Default::default(), &());
+        let white = Rgba([255, 255, 255, 255]);
+
+        let pixels = rasterize_text(fonts.bundled(), "\u{21EA}", white, 20, 40).unwrap();
+        let columns: Vec<u32> = (0..20)
+            .filter(|x| (0..40).any(|y| pixels[y * 20 + x] != 0))
+            .map(|x| x as u32)
+            .collect();
+        assert!(columns.len() >= 18, "glyph should span the area width");
+
+        assert!(rasterize_text(fonts.bundled(), " ", white, 20, 40).is_err());
+    }
+
+    #[test]
+    fn label_uses_style_size_spacing_and_plate() {
+        let fonts = FontChain::new(&Default::default(), &());
+        let white = Rgba([255, 255, 255, 255]);
+        let black = Rgba([0, 0, 0, 255]);
+        let text = "\u{21EA}\u{21EA}";
+
+        let ink_width = |pixels: &[u32]| {
+            let columns: Vec<usize> = (0..100)
+                .filter(|x| (0..50).any(|y| pixels[y * 100 + x] != 0))
+                .collect();
+            columns.last().unwrap() - columns.first().unwrap() + 1
+        };
+
+        let style = IndicatorStyle {
+            size: 0.4,
+            letter_spacing: 0.0,
+            ..Default::default()
+        };
+        let tight = rasterize_label(fonts.bundled(), text, white, None, 100, 50, &style).unwrap();
+        let style = IndicatorStyle {
+            letter_spacing: 0.5,
+            ..style
+        };
+        let spaced = rasterize_label(fonts.bundled(), text, white, None, 100, 50, &style).unwrap();
+        assert!(ink_width(&spaced) > ink_width(&tight));
+        assert!(ink_width(&tight) < 50, "label should not fill the area");
+
+        let plated =
+            rasterize_label(fonts.bundled(), text, white, Some(black), 100, 50, &style).unwrap();
+        assert!(ink_width(&plated) > ink_width(&spaced));
+        assert_eq!(plated[0], 0, "plate corners are rounded");
+        assert_eq!(plated[25 * 100 + 50] >> 24, 0xFF);
+    }
+
+    #[test]
+    fn buffer_converts_back_to_straight_alpha() {
+        let buffer = [(128 << 24) | (128 << 16), 0xFF00FF00, 0];
+        let image = buffer_to_rgba_image(&buffer, 3, 1);
+
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 128]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(2, 0).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn glyph_effects_are_drawn_beneath_the_glyph() {
+        let white = 0xFFFFFFFF;
+        let black = Rgba([0, 0, 0, 255]);
+        let mut pixels = vec![0u32; 5 * 5];
+        pixels[2 * 5 + 2] = white;
+
+        let outlined = apply_glyph_effect(&pixels, 5, 5, GlyphEffect::Outline, black);
+        assert_eq!(outlined[2 * 5 + 2], white);
+        for idx in [5 + 2, 2 * 5 + 1, 2 * 5 + 3, 3 * 5 + 2] {
+            assert_eq!(outlined[idx], 0xFF000000);
+        }
+        assert_eq!(outlined[0], 0);
+
+        let shadowed = apply_glyph_effect(&pixels, 5, 5, GlyphEffect::Shadow, black);
+        assert_eq!(shadowed[2 * 5 + 2], white);
+        assert_eq!(shadowed[3 * 5 + 3] >> 24, 153);
+        assert_eq!(shadowed[5 + 1], 0);
+
+        assert_eq!(
+            apply_glyph_effect(&pixels, 5, 5, GlyphEffect::None, black),
+            pixels
+        );
+    }
+}
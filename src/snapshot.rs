@@ -0,0 +1,168 @@
+//! 无窗口渲染：`CapsGlow --render-snapshot out.png [--theme light|dark] [--size <px>] [--keys caps,num,scroll]`
+//!
+//! 将指示器窗口会显示的内容写入 PNG，用于图像回归测试与预览自定义图标。
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::{
+    config::Config,
+    icon::{CustomIcon, indicator_window_size},
+    key::{KeyStates, LockKey},
+    osd::Indicator,
+    render::{IndicatorRenderer, buffer_to_rgba_image},
+    theme::SystemTheme,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotOptions {
+    pub output: PathBuf,
+    pub theme: SystemTheme,
+    /// 窗口的物理边长，未指定时与指示器窗口一致（缩放比例按 1.0 计算）
+    pub size: Option<u32>,
+    pub key_states: KeyStates,
+}
+
+impl SnapshotOptions {
+    /// 解析命令行参数，未指定 `--render-snapshot` 时返回 `None`
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter();
+        let mut output = None;
+        let mut theme = SystemTheme::Light;
+        let mut size = None;
+        let mut key_states = KeyStates::default();
+        key_states.set(LockKey::Caps, true);
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("Missing value for '{arg}'"))
+            };
+
+            match arg.as_str() {
+                "--render-snapshot" => output = Some(PathBuf::from(value()?)),
+                "--theme" => {
+                    theme = match value()?.to_ascii_lowercase().as_str() {
+                        "light" => SystemTheme::Light,
+                        "dark" => SystemTheme::Dark,
+                        other => return Err(anyhow!("Unknown theme '{other}'")),
+                    }
+                }
+                "--size" => {
+                    let value = value()?;
+                    size = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|size| *size > 0)
+                            .with_context(|| format!("Invalid size '{value}'"))?,
+                    );
+                }
+                "--keys" => {
+                    key_states = KeyStates::default();
+                    for name in value()?.split(',').filter(|name| !name.is_empty()) {
+                        let key = match name.trim().to_ascii_lowercase().as_str() {
+                            "caps" => LockKey::Caps,
+                            "num" => LockKey::Num,
+                            "scroll" => LockKey::Scroll,
+                            other => return Err(anyhow!("Unknown lock key '{other}'")),
+                        };
+                        key_states.set(key, true);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(output.map(|output| SnapshotOptions {
+            output,
+            theme,
+            size,
+            key_states,
+        }))
+    }
+}
+
+pub fn render_snapshot(options: &SnapshotOptions) -> Result<()> {
+    let config = Config::read_or_default();
+    #[cfg(target_os = "windows")]
+    let locator = crate::platform::WindowsPlatform;
+    #[cfg(not(target_os = "windows"))]
+    let locator = ();
+
+    let mut renderer = IndicatorRenderer::new(
+        CustomIcon::find_custom_icons(),
+        &config.get_font_setting(),
+        locator,
+    );
+    renderer.set_style(&config.get_indicator_style());
+    renderer.set_palette(&config.get_palette());
+
+    let (width, height) = options.size.map_or_else(
+        || indicator_window_size(renderer.custom_icons(), config.get_indicator_size(), 1.0),
+        |s| (s, s),
+    );
+
+    let watched_keys = config.watched_keys.lock().unwrap();
+    let indicators: Vec<Indicator> = watched_keys
+        .active_indicators(options.key_states)
+        .into_iter()
+        .map(Indicator::Key)
+        .collect();
+
+    let mut buffer = vec![0u32; (width * height) as usize];
+    renderer.render(
+        &mut buffer,
+        width,
+        height,
+        &indicators,
+        options.theme.into(),
+        &watched_keys,
+    );
+
+    buffer_to_rgba_image(&buffer, width, height)
+        .save(&options.output)
+        .with_context(|| format!("Failed to save snapshot to {}", options.output.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<SnapshotOptions>> {
+        SnapshotOptions::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_snapshot_arguments() {
+        assert_eq!(parse(&[]).unwrap(), None);
+
+        let options = parse(&[
+            "--render-snapshot",
+            "out.png",
+            "--theme",
+            "dark",
+            "--size",
+            "64",
+            "--keys",
+            "num,scroll",
+        ])
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(options.output, PathBuf::from("out.png"));
+        assert_eq!(options.theme, SystemTheme::Dark);
+        assert_eq!(options.size, Some(64));
+        assert!(!options.key_states.is_on(LockKey::Caps));
+        assert!(options.key_states.is_on(LockKey::Num));
+        assert!(options.key_states.is_on(LockKey::Scroll));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(parse(&["--render-snapshot", "out.png", "--size", "0"]).is_err());
+        assert!(parse(&["--render-snapshot", "out.png", "--theme", "blue"]).is_err());
+        assert!(parse(&["--render-snapshot"]).is_err());
+    }
+}
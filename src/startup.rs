@@ -1,39 +1,56 @@
 use super::config::{EXE_NAME, EXE_PATH_STRING};
+use crate::platform::Registry;
 
-use anyhow::{Context, Result, anyhow};
-use winreg::{
-    RegKey,
-    enums::{HKEY_CURRENT_USER, KEY_READ},
-};
+use anyhow::{Context, Result};
 
 const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
 
-pub fn set_startup(enabled: bool) -> Result<()> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let (run_key, _disp) = hkcu.create_subkey(RUN_KEY)?;
-
+pub fn set_startup(registry: &impl Registry, enabled: bool) -> Result<()> {
     if enabled {
-        run_key
-            .set_value(&*EXE_NAME, &*EXE_PATH_STRING)
+        registry
+            .set_string(RUN_KEY, &EXE_NAME, &EXE_PATH_STRING)
             .with_context(|| "Failed to set the autostart registry key")?;
     } else {
-        run_key
-            .delete_value(&*EXE_NAME)
+        registry
+            .delete_value(RUN_KEY, &EXE_NAME)
             .with_context(|| "Failed to delete the autostart registry key")?;
     }
 
     Ok(())
 }
 
-pub fn get_startup_status() -> Result<bool> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let run_key = hkcu
-        .open_subkey_with_flags(RUN_KEY, KEY_READ)
-        .map_err(|e| anyhow!("Failed to open HKEY_CURRENT_USER\\...\\Run - {e}"))?;
+pub fn get_startup_status(registry: &impl Registry) -> Result<bool> {
+    let value = registry
+        .get_string(RUN_KEY, &EXE_NAME)
+        .with_context(|| "Failed to get the autostart registry key")?;
+
+    Ok(value.is_some_and(|value| value == *EXE_PATH_STRING))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::fake::FakePlatform;
+
+    #[test]
+    fn toggles_the_run_key() {
+        let platform = FakePlatform::default();
+        assert!(!get_startup_status(&platform).unwrap());
+
+        set_startup(&platform, true).unwrap();
+        assert!(get_startup_status(&platform).unwrap());
+
+        set_startup(&platform, false).unwrap();
+        assert!(!get_startup_status(&platform).unwrap());
+    }
+
+    #[test]
+    fn ignores_entries_pointing_elsewhere() {
+        let platform = FakePlatform::default();
+        platform
+            .set_string(RUN_KEY, &EXE_NAME, r"C:\Other\CapsGlow.exe")
+            .unwrap();
 
-    match run_key.get_value::<String, _>(&*EXE_NAME) {
-        Ok(value) => Ok(value == *EXE_PATH_STRING),
-        Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
-        Err(e) => Err(anyhow!("Failed to get the autostart registry key - {e}")),
+        assert!(!get_startup_status(&platform).unwrap());
     }
 }
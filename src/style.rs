@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::font::{FontSetting, FontSource};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StyleKind {
+    /// 绘制单个字符（默认为 🔒 等 emoji）
+    #[default]
+    Glyph,
+    /// 绘制文字标签，如 `CAPS`、`A`、`1`
+    Text,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FontWeight {
+    Light,
+    #[default]
+    Regular,
+    Semibold,
+    Bold,
+}
+
+impl FontWeight {
+    /// 系统字体名称中表示字重的后缀，如 `Segoe UI Bold`
+    fn family_suffix(&self) -> Option<&'static str> {
+        match self {
+            FontWeight::Light => Some("Light"),
+            FontWeight::Regular => None,
+            FontWeight::Semibold => Some("Semibold"),
+            FontWeight::Bold => Some("Bold"),
+        }
+    }
+}
+
+/// 字形周围的效果，使字形在复杂的壁纸上依然清晰
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GlyphEffect {
+    #[default]
+    None,
+    /// 在字形四周描边
+    Outline,
+    /// 在字形右下方绘制阴影
+    Shadow,
+}
+
+/// 指示器样式，除 `kind` 与 `effect` 外的字段只用于文字标签
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorStyle {
+    pub kind: StyleKind,
+    pub font: FontSource,
+    /// 找不到对应字重的字体时使用常规字重
+    pub weight: FontWeight,
+    /// 文字字号占窗口边长（`IndicatorSize`）的比例，文字过宽时会自动缩小
+    pub size: f32,
+    /// 额外的字间距，按字号的比例计算
+    pub letter_spacing: f32,
+    pub background_plate: bool,
+    /// 背景圆角半径占背景高度的比例，0.5 为两端半圆
+    pub plate_radius: f32,
+    /// 字形的描边或阴影，文字标签绘制背景时不使用
+    pub effect: GlyphEffect,
+}
+
+impl Default for IndicatorStyle {
+    fn default() -> Self {
+        Self {
+            kind: StyleKind::default(),
+            font: FontSource::Family("Segoe UI".to_owned()),
+            weight: FontWeight::default(),
+            size: 0.3,
+            letter_spacing: 0.05,
+            background_plate: false,
+            plate_radius: 0.3,
+            effect: GlyphEffect::default(),
+        }
+    }
+}
+
+impl IndicatorStyle {
+    /// 文字标签使用的字体：先找对应字重的字体，再回退到常规字重
+    pub fn text_font_setting(&self) -> FontSetting {
+        let font = match (&self.font, self.weight.family_suffix()) {
+            (FontSource::Family(family), Some(suffix)) => {
+                FontSource::Family(format!("{family} {suffix}"))
+            }
+            (font, _) => font.clone(),
+        };
+
+        FontSetting {
+            fallback_fonts: vec![self.font.clone()],
+            font,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weight_selects_the_family_variant() {
+        let style = IndicatorStyle {
+            weight: FontWeight::Bold,
+            ..Default::default()
+        };
+        let setting = style.text_font_setting();
+
+        assert_eq!(setting.font, FontSource::Family("Segoe UI Bold".to_owned()));
+        assert_eq!(
+            setting.fallback_fonts,
+            vec![FontSource::Family("Segoe UI".to_owned())]
+        );
+
+        let style = IndicatorStyle {
+            font: FontSource::File("label.ttf".into()),
+            weight: FontWeight::Bold,
+            ..Default::default()
+        };
+        assert_eq!(
+            style.text_font_setting().font,
+            FontSource::File("label.ttf".into())
+        );
+    }
+}
@@ -1,58 +1,68 @@
-use crate::util::to_wide;
+use std::time::Duration;
 
 use image::Rgba;
 use serde::{Deserialize, Serialize};
-use windows::{
-    Win32::{
-        Graphics::Gdi::{
-            BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BitBlt, CreateCompatibleBitmap,
-            CreateCompatibleDC, DIB_RGB_COLORS, DeleteDC, DeleteObject, GetDC, GetDIBits,
-            GetDeviceCaps, HORZRES, SRCCOPY, SelectObject, VERTRES,
-        },
-        System::Registry::{HKEY_CURRENT_USER, REG_DWORD, RRF_RT_REG_DWORD, RegGetValueW},
-    },
-    core::PCWSTR,
-};
+
+use crate::contrast::best_foreground;
+use crate::platform::{AccentSource, MonitorProvider, Rect, Registry, ScreenSampler};
 
 const PERSONALIZE_REGISTRY_KEY: &str =
     r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
 const SYSTEM_USES_LIGHT_THEME_REGISTRY_KEY: &str = "SystemUsesLightTheme";
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SystemTheme {
     Light,
     Dark,
 }
 
 impl SystemTheme {
-    fn get() -> Self {
-        let path = to_wide(PERSONALIZE_REGISTRY_KEY);
-        let name = to_wide(SYSTEM_USES_LIGHT_THEME_REGISTRY_KEY);
-
-        let mut value: u32 = 0;
-        let mut size = std::mem::size_of::<u32>() as u32;
-        let mut reg_dword = REG_DWORD;
-
-        let ret = unsafe {
-            RegGetValueW(
-                HKEY_CURRENT_USER,
-                PCWSTR(path.as_ptr()),
-                PCWSTR(name.as_ptr()),
-                RRF_RT_REG_DWORD,
-                Some(&mut reg_dword),
-                Some(&mut value as *mut _ as *mut _),
-                Some(&mut size as *mut _),
-            )
-        };
+    fn get(registry: &impl Registry) -> Self {
+        match registry.get_dword(
+            PERSONALIZE_REGISTRY_KEY,
+            SYSTEM_USES_LIGHT_THEME_REGISTRY_KEY,
+        ) {
+            Some(0) => SystemTheme::Dark,
+            _ => SystemTheme::Light,
+        }
+    }
 
-        if ret.is_err() {
-            SystemTheme::Light
-        } else {
-            match value {
-                0 => SystemTheme::Dark,
-                _ => SystemTheme::Light,
-            }
+    /// BGRA 像素（GDI 顺序）的平均亮度，范围 0–1，没有像素时返回 `None`
+    pub fn average_luminance(pixels: &[u8]) -> Option<f32> {
+        let count = pixels.len() / 4;
+        if count == 0 {
+            return None;
+        }
+
+        let total_brightness: f32 = pixels
+            .chunks_exact(4)
+            .map(|chunk| {
+                // 注意Windows的GDI返回BGR格式
+                let r = chunk[2] as f32;
+                let g = chunk[1] as f32;
+                let b = chunk[0] as f32;
+                0.2126 * r + 0.7152 * g + 0.0722 * b // 亮度计算公式
+            })
+            .sum();
+
+        Some(total_brightness / (count * 255) as f32)
+    }
+
+    /// BGRA 像素的平均颜色 `[r, g, b]`，没有像素时返回 `None`
+    pub fn average_color(pixels: &[u8]) -> Option<[u8; 3]> {
+        let count = (pixels.len() / 4) as u64;
+        if count == 0 {
+            return None;
+        }
+
+        let mut sum = [0u64; 3];
+        for chunk in pixels.chunks_exact(4) {
+            sum[0] += chunk[2] as u64;
+            sum[1] += chunk[1] as u64;
+            sum[2] += chunk[0] as u64;
         }
+
+        Some(sum.map(|channel| (channel / count) as u8))
     }
 
     pub fn get_font_color(&self) -> Rgba<u8> {
@@ -61,6 +71,95 @@ impl SystemTheme {
             Self::Light => Rgba([31, 31, 31, 255]),
         }
     }
+
+    /// 文字标签的背景颜色，与文字颜色形成对比
+    pub fn get_plate_color(&self) -> Rgba<u8> {
+        match self {
+            Self::Dark => Rgba([31, 31, 31, 192]),
+            Self::Light => Rgba([255, 255, 255, 208]),
+        }
+    }
+}
+
+/// 跟随指示器区域背景（`IndicatorTheme::IndicatorArea`）时的取样设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveThemeSetting {
+    /// 背景平均亮度（0–1）高于此值时使用浅色主题（深色字形）
+    pub threshold: f32,
+    /// 切换主题时亮度需越过阈值的距离，背景亮度在阈值附近时颜色不会来回闪烁
+    pub hysteresis: f32,
+    /// 指示器显示期间重新取样背景的间隔（毫秒），0 为只在重绘时取样
+    pub interval_ms: u64,
+    /// 对比度模式（`IndicatorTheme::Contrast`）下前景色与背景的目标 WCAG 对比度，范围 1–21
+    pub contrast_ratio: f32,
+}
+
+impl Default for AdaptiveThemeSetting {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            hysteresis: 0.05,
+            interval_ms: 500,
+            contrast_ratio: 4.5,
+        }
+    }
+}
+
+impl AdaptiveThemeSetting {
+    pub fn interval(&self) -> Option<Duration> {
+        (self.interval_ms > 0).then(|| Duration::from_millis(self.interval_ms))
+    }
+
+    /// 根据背景亮度选择主题，`previous` 为当前使用的主题
+    ///
+    /// 当前为浅色主题时，亮度需低于 `threshold - hysteresis` 才切换为深色主题，反之亦然
+    pub fn decide(&self, luminance: f32, previous: Option<SystemTheme>) -> SystemTheme {
+        let hysteresis = self.hysteresis.max(0.0);
+        let threshold = match previous {
+            Some(SystemTheme::Light) => self.threshold - hysteresis,
+            Some(SystemTheme::Dark) => self.threshold + hysteresis,
+            None => self.threshold,
+        };
+
+        if luminance > threshold {
+            SystemTheme::Light
+        } else {
+            SystemTheme::Dark
+        }
+    }
+
+    /// 根据 BGRA 像素的平均亮度选择主题，背景偏亮时使用浅色主题（深色字形）
+    pub fn theme_from_bgra_pixels(
+        &self,
+        pixels: &[u8],
+        previous: Option<SystemTheme>,
+    ) -> SystemTheme {
+        SystemTheme::average_luminance(pixels).map_or(SystemTheme::Light, |luminance| {
+            self.decide(luminance, previous)
+        })
+    }
+}
+
+/// 绘制指示器使用的颜色
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndicatorColors {
+    /// 决定使用哪一套自定义图标
+    pub theme: SystemTheme,
+    /// 对比度模式下根据背景计算的字形与文字颜色，为 `None` 时使用调色板（`Palette`）中的颜色
+    pub font: Option<Rgba<u8>>,
+    /// 文字标签的背景颜色
+    pub plate: Rgba<u8>,
+}
+
+impl From<SystemTheme> for IndicatorColors {
+    fn from(theme: SystemTheme) -> Self {
+        Self {
+            theme,
+            font: None,
+            plate: theme.get_plate_color(),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -68,97 +167,274 @@ pub enum IndicatorTheme {
     System,
     #[default]
     IndicatorArea,
+    /// 根据指示器区域背景的颜色计算前景色，使对比度达到 `AdaptiveThemeSetting::contrast_ratio`
+    Contrast,
+    /// 使用系统的强调色（DWM 着色颜色），背景与自定义图标跟随系统主题
+    Accent,
 }
 
 impl IndicatorTheme {
-    pub fn get_theme(&self, scale: f64, window_phy_size: f64) -> SystemTheme {
+    /// 是否根据指示器窗口背后的背景决定颜色
+    pub fn samples_background(&self) -> bool {
+        matches!(
+            self,
+            IndicatorTheme::IndicatorArea | IndicatorTheme::Contrast
+        )
+    }
+
+    /// `previous` 为窗口当前使用的主题，跟随背景时用于防止颜色在阈值附近闪烁
+    ///
+    /// `window_rect` 为指示器窗口的物理矩形，跟随背景时取样该区域
+    pub fn get_theme(
+        &self,
+        platform: &(impl Registry + ScreenSampler + MonitorProvider),
+        window_rect: Rect,
+        adaptive: &AdaptiveThemeSetting,
+        previous: Option<SystemTheme>,
+    ) -> SystemTheme {
         match self {
-            IndicatorTheme::System => SystemTheme::get(),
-            IndicatorTheme::IndicatorArea => Self::get_indicator_area_theme(scale, window_phy_size),
+            IndicatorTheme::System | IndicatorTheme::Accent => SystemTheme::get(platform),
+            IndicatorTheme::IndicatorArea | IndicatorTheme::Contrast => {
+                sample_background(platform, window_rect).map_or(SystemTheme::Light, |pixels| {
+                    adaptive.theme_from_bgra_pixels(&pixels, previous)
+                })
+            }
         }
     }
 
-    fn get_indicator_area_theme(scale: f64, window_phy_size: f64) -> SystemTheme {
-        unsafe {
-            let hdc_screen = GetDC(None);
-
-            let img_size = (window_phy_size * scale) as i32;
-            let screen_width = GetDeviceCaps(Some(hdc_screen), HORZRES);
-            let screen_height = GetDeviceCaps(Some(hdc_screen), VERTRES);
-            if screen_width < img_size || screen_height < img_size {
-                return SystemTheme::Light;
+    /// 绘制指示器使用的颜色
+    ///
+    /// 对比度模式下前景色随背景颜色连续变化，强调色模式下使用系统的强调色，其余主题使用调色板中的颜色
+    pub fn get_colors(
+        &self,
+        platform: &(impl Registry + ScreenSampler + MonitorProvider + AccentSource),
+        window_rect: Rect,
+        adaptive: &AdaptiveThemeSetting,
+        previous: Option<SystemTheme>,
+    ) -> IndicatorColors {
+        match self {
+            IndicatorTheme::Contrast => {
+                Self::get_contrast_colors(platform, window_rect, adaptive, previous)
             }
+            // 获取不到强调色时使用调色板中的颜色
+            IndicatorTheme::Accent => IndicatorColors {
+                font: platform
+                    .accent_color()
+                    .map(|[r, g, b]| Rgba([r, g, b, 255])),
+                ..SystemTheme::get(platform).into()
+            },
+            IndicatorTheme::System | IndicatorTheme::IndicatorArea => self
+                .get_theme(platform, window_rect, adaptive, previous)
+                .into(),
+        }
+    }
+
+    fn get_contrast_colors(
+        platform: &(impl ScreenSampler + MonitorProvider),
+        window_rect: Rect,
+        adaptive: &AdaptiveThemeSetting,
+        previous: Option<SystemTheme>,
+    ) -> IndicatorColors {
+        let Some(pixels) = sample_background(platform, window_rect) else {
+            return SystemTheme::Light.into();
+        };
+        let theme = adaptive.theme_from_bgra_pixels(&pixels, previous);
+        let font = SystemTheme::average_color(&pixels).map(|background| {
+            let [r, g, b] = best_foreground(background, adaptive.contrast_ratio);
+            Rgba([r, g, b, 255])
+        });
+        IndicatorColors {
+            font,
+            ..theme.into()
+        }
+    }
+}
+
+/// 截取指示器窗口背后的背景
+fn sample_background(
+    platform: &(impl ScreenSampler + MonitorProvider),
+    window_rect: Rect,
+) -> Option<Vec<u8>> {
+    indicator_area_sample_rect(platform, window_rect).and_then(|rect| platform.capture(rect))
+}
+
+/// 取样背景亮度的区域：指示器窗口与其所在显示器重叠的部分
+///
+/// 窗口超出显示器边缘时只取样显示器内的部分，窗口不在任何显示器上时返回 `None`
+pub fn indicator_area_sample_rect(
+    monitors: &impl MonitorProvider,
+    window_rect: Rect,
+) -> Option<Rect> {
+    let area = monitors
+        .monitor_area_from_point(window_rect.center())
+        .ok()?;
+    window_rect.intersection(area.monitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contrast::contrast_ratio;
+    use crate::platform::fake::FakePlatform;
+
+    const WINDOW_RECT: Rect = Rect {
+        left: 860,
+        top: 440,
+        right: 1060,
+        bottom: 640,
+    };
+
+    #[test]
+    fn system_theme_reads_the_registry() {
+        let platform = FakePlatform::default();
+        assert_eq!(
+            IndicatorTheme::System.get_theme(&platform, WINDOW_RECT, &Default::default(), None),
+            SystemTheme::Light
+        );
+
+        platform.set_dword(
+            PERSONALIZE_REGISTRY_KEY,
+            SYSTEM_USES_LIGHT_THEME_REGISTRY_KEY,
+            0,
+        );
+        assert_eq!(
+            IndicatorTheme::System.get_theme(&platform, WINDOW_RECT, &Default::default(), None),
+            SystemTheme::Dark
+        );
+    }
 
-            let x_start = (screen_width - img_size) / 2;
-            let y_start = (screen_height - img_size) / 2;
-            let hdc_mem = CreateCompatibleDC(Some(hdc_screen));
-            let h_bitmap = CreateCompatibleBitmap(hdc_screen, img_size, img_size);
-            let _old_bitmap = SelectObject(hdc_mem, h_bitmap.into());
-
-            // Capture the target area
-            if BitBlt(
-                hdc_mem,
-                0,
-                0,
-                img_size,
-                img_size,
-                Some(hdc_screen),
-                x_start,
-                y_start,
-                SRCCOPY,
+    #[test]
+    fn indicator_area_theme_follows_background_brightness() {
+        let platform = FakePlatform::default();
+        let theme = || {
+            IndicatorTheme::IndicatorArea.get_theme(
+                &platform,
+                WINDOW_RECT,
+                &Default::default(),
+                None,
             )
-            .is_err()
-            {
-                return SystemTheme::Light;
-            };
-
-            // Preparing the BITMAPINFO structure
-            let mut bmi = BITMAPINFO {
-                bmiHeader: BITMAPINFOHEADER {
-                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
-                    biWidth: img_size,
-                    biHeight: -img_size, // 从上到下排列
-                    biPlanes: 1,
-                    biBitCount: 32,
-                    biCompression: BI_RGB.0,
-                    ..Default::default()
-                },
-                ..Default::default()
-            };
-
-            let mut buffer = vec![0u8; (img_size * img_size * 4) as usize];
-            GetDIBits(
-                hdc_mem,
-                h_bitmap,
-                0,
-                img_size as u32,
-                Some(buffer.as_mut_ptr() as *mut _),
-                &mut bmi as *mut _ as *mut _,
-                DIB_RGB_COLORS,
-            );
-
-            let total_brightness: f32 = buffer
-                .chunks_exact(4)
-                .map(|chunk| {
-                    // 注意Windows的GDI返回BGR格式
-                    let r = chunk[2] as f32;
-                    let g = chunk[1] as f32;
-                    let b = chunk[0] as f32;
-                    0.2126 * r + 0.7152 * g + 0.0722 * b // 亮度计算公式
-                })
-                .sum();
+        };
+
+        platform.screen_color.set([255, 255, 255]);
+        assert_eq!(theme(), SystemTheme::Light);
 
-            let avg = total_brightness / (img_size * img_size * 255) as f32;
+        platform.screen_color.set([30, 30, 30]);
+        assert_eq!(theme(), SystemTheme::Dark);
+    }
+
+    #[test]
+    fn adaptive_theme_does_not_flicker_around_the_threshold() {
+        let adaptive = AdaptiveThemeSetting::default();
+        let gray = |level: u8| [level, level, level, 255].repeat(16);
+
+        // 0.53 与 0.47 都在阈值 ± 0.05 以内，保持当前主题
+        let light_gray = gray(135);
+        let dark_gray = gray(120);
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&light_gray, None),
+            SystemTheme::Light
+        );
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&dark_gray, Some(SystemTheme::Light)),
+            SystemTheme::Light
+        );
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&light_gray, Some(SystemTheme::Dark)),
+            SystemTheme::Dark
+        );
+
+        // 越过阈值足够远时切换
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&gray(100), Some(SystemTheme::Light)),
+            SystemTheme::Dark
+        );
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&gray(160), Some(SystemTheme::Dark)),
+            SystemTheme::Light
+        );
+
+        // 可配置阈值，没有像素时使用浅色主题
+        let bright_threshold = AdaptiveThemeSetting {
+            threshold: 0.8,
+            ..Default::default()
+        };
+        assert_eq!(
+            bright_threshold.theme_from_bgra_pixels(&gray(160), None),
+            SystemTheme::Dark
+        );
+        assert_eq!(
+            adaptive.theme_from_bgra_pixels(&[], Some(SystemTheme::Dark)),
+            SystemTheme::Light
+        );
+    }
+
+    #[test]
+    fn background_is_sampled_under_the_window_on_its_own_monitor() {
+        let secondary = Rect::new(1920, 0, 3840, 1080);
+        let platform = FakePlatform::with_monitors(vec![Rect::new(0, 0, 1920, 1080), secondary]);
 
-            DeleteObject(h_bitmap.into()).unwrap();
-            DeleteDC(hdc_mem).unwrap();
-            DeleteDC(hdc_screen).unwrap();
+        // 窗口在副显示器上时取样窗口所在的区域，而不是主显示器的中央
+        let window = Rect::new(2780, 880, 2980, 1080);
+        assert_eq!(indicator_area_sample_rect(&platform, window), Some(window));
 
-            if avg > 0.5 {
-                SystemTheme::Light
-            } else {
-                SystemTheme::Dark
+        // 超出显示器边缘的部分不取样
+        let overhanging = Rect::new(3740, 980, 3940, 1180);
+        assert_eq!(
+            indicator_area_sample_rect(&platform, overhanging),
+            Some(Rect::new(3740, 980, 3840, 1080))
+        );
+
+        assert_eq!(indicator_area_sample_rect(&platform, Rect::default()), None);
+    }
+
+    #[test]
+    fn contrast_theme_picks_a_legible_colour_for_the_background() {
+        let platform = FakePlatform::default();
+        let colors = || {
+            IndicatorTheme::Contrast.get_colors(&platform, WINDOW_RECT, &Default::default(), None)
+        };
+
+        platform.screen_color.set([20, 30, 90]);
+        let navy = colors();
+        let Some(Rgba([r, g, b, _])) = navy.font else {
+            panic!("contrast theme must compute the font colour");
+        };
+        assert_eq!(navy.theme, SystemTheme::Dark);
+        assert!(contrast_ratio([r, g, b], [20, 30, 90]) >= 4.5);
+        assert_ne!([r, g, b, 255], SystemTheme::Dark.get_font_color().0);
+
+        platform.screen_color.set([240, 240, 240]);
+        assert_eq!(colors().theme, SystemTheme::Light);
+        assert_eq!(
+            IndicatorTheme::System
+                .get_colors(&platform, WINDOW_RECT, &Default::default(), None)
+                .font,
+            None
+        );
+    }
+
+    #[test]
+    fn accent_theme_uses_the_dwm_colour_over_the_system_theme() {
+        let platform = FakePlatform::default();
+        platform.set_dword(
+            PERSONALIZE_REGISTRY_KEY,
+            SYSTEM_USES_LIGHT_THEME_REGISTRY_KEY,
+            0,
+        );
+        let colors =
+            || IndicatorTheme::Accent.get_colors(&platform, WINDOW_RECT, &Default::default(), None);
+
+        platform.accent_color.set(Some([232, 17, 35]));
+        assert_eq!(
+            colors(),
+            IndicatorColors {
+                theme: SystemTheme::Dark,
+                font: Some(Rgba([232, 17, 35, 255])),
+                plate: SystemTheme::Dark.get_plate_color(),
             }
-        }
+        );
+
+        platform.accent_color.set(None);
+        assert_eq!(colors().font, None);
     }
 }
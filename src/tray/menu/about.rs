@@ -1,5 +1,5 @@
-use crate::language::LOC;
-use crate::util::to_wide;
+use capsglow::language::LOC;
+use capsglow::util::to_wide;
 
 use windows::Win32::Foundation::{HINSTANCE, HWND};
 use windows::Win32::UI::Controls::{
@@ -1,7 +1,9 @@
 use super::{MenuGroup, item::*};
-use crate::{
-    UserEvent,
+use crate::app::UserEvent;
+use capsglow::{
     config::{CONFIG_PATH, Config},
+    monitor::MonitorId,
+    platform::WindowsPlatform,
     startup::set_startup,
 };
 
@@ -55,6 +57,10 @@ impl MenuHandler {
                 proxy
                     .send_event(UserEvent::Restart)
                     .context("Failed to send 'Restart' event")
+            } else if id.eq(&*PLACE_BY_DRAGGING) {
+                proxy
+                    .send_event(UserEvent::StartPlacing)
+                    .context("Failed to send 'StartPlacing' event")
             } else if id.eq(&*OPEN_CONFIG) {
                 Command::new("notepad.exe")
                     .arg(&*CONFIG_PATH)
@@ -67,12 +73,33 @@ impl MenuHandler {
         } else if let Some((check_menu, group)) = &self.check_menu {
             if let Some(group) = group {
                 match group {
+                    // GroupMulti
+                    MenuGroup::LockKey => {
+                        let Some(check_menu) = check_menu else {
+                            return Err(anyhow!("The clicked Lock Key menu is missing: {}", id.0));
+                        };
+
+                        if let Some((_, key)) = LOCK_KEYS.iter().find(|(menu_id, _)| menu_id == id)
+                        {
+                            config.set_key_enabled(*key, check_menu.is_checked());
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::RedrawRequested)
+                                .context("Failed to send 'RedrawRequested' event");
+                        }
+                        Ok(())
+                    }
                     // GroupSingle
                     MenuGroup::IndicatorIconTheme => {
                         if id == &*FOLLOW_INDICATOR_AREA_THEME {
                             config.set_indicator_indicator_area_theme();
                         } else if id == &*FOLLOW_SYSTEM_THEME {
                             config.set_indicator_system_theme();
+                        } else if id == &*FOLLOW_CONTRAST_THEME {
+                            config.set_indicator_contrast_theme();
+                        } else if id == &*FOLLOW_ACCENT_THEME {
+                            config.set_indicator_accent_theme();
                         } else {
                             // ...
                         }
@@ -86,13 +113,97 @@ impl MenuHandler {
                         Ok(())
                     }
                     // GroupSingle
+                    MenuGroup::PalettePreset => {
+                        if let Some((_, preset, _)) =
+                            PALETTE_PRESETS.iter().find(|(menu_id, _, _)| menu_id == id)
+                        {
+                            config.set_palette_preset(*preset);
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::RedrawRequested)
+                                .context("Failed to send 'RedrawRequested' event");
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
+                    MenuGroup::IndicatorStyle => {
+                        if let Some((_, kind, _)) =
+                            STYLE_KINDS.iter().find(|(menu_id, _, _)| menu_id == id)
+                        {
+                            config.set_style_kind(*kind);
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::RedrawRequested)
+                                .context("Failed to send 'RedrawRequested' event");
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
+                    MenuGroup::FontWeight => {
+                        if let Some((_, weight, _)) =
+                            FONT_WEIGHTS.iter().find(|(menu_id, _, _)| menu_id == id)
+                        {
+                            config.set_font_weight(*weight);
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::RedrawRequested)
+                                .context("Failed to send 'RedrawRequested' event");
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
+                    MenuGroup::IndicatorSize => {
+                        if let Some((_, size, _)) =
+                            INDICATOR_SIZES.iter().find(|(menu_id, _, _)| menu_id == id)
+                        {
+                            config.set_indicator_size(*size);
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::MoveWindow)
+                                .context("Failed to send 'Move Window' event");
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
+                    MenuGroup::DisplayMode => {
+                        if let Some((_, mode, _)) =
+                            DISPLAY_MODES.iter().find(|(menu_id, _, _)| menu_id == id)
+                        {
+                            config.set_display_mode(*mode);
+                            config.save();
+
+                            let _ = proxy
+                                .send_event(UserEvent::RedrawRequested)
+                                .context("Failed to send 'RedrawRequested' event");
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
+                    MenuGroup::OsdDuration => {
+                        if let Some((_, duration_ms)) =
+                            OSD_DURATIONS.iter().find(|(menu_id, _)| menu_id == id)
+                        {
+                            config.set_osd_duration(*duration_ms);
+                            config.save();
+                        }
+                        Ok(())
+                    }
+                    // GroupSingle
                     MenuGroup::MonitorSelector => {
                         if id == &*SELECT_MOUSE_MONITOR {
                             config.set_mouse_monitor();
                         } else if id == &*SELECT_PRIMARY_MONITOR {
                             config.set_primary_monitor();
-                        } else {
-                            // ...
+                        } else if id == &*SELECT_ALL_MONITORS {
+                            config.set_all_monitors();
+                        } else if let Some(monitor_id) =
+                            id.0.strip_prefix(SELECT_SPECIFIC_MONITOR_PREFIX)
+                        {
+                            config.set_specific_monitor(MonitorId(monitor_id.to_owned()));
                         }
 
                         config.save();
@@ -129,7 +240,18 @@ impl MenuHandler {
                 };
 
                 if id.eq(&*STARTUP) {
-                    set_startup(check_menu.is_checked())
+                    set_startup(&WindowsPlatform, check_menu.is_checked())
+                } else if id.eq(&*OSD_SHOW_OFF) {
+                    config.set_osd_show_off(check_menu.is_checked());
+                    config.save();
+                    Ok(())
+                } else if id.eq(&*BACKGROUND_PLATE) {
+                    config.set_background_plate(check_menu.is_checked());
+                    config.save();
+
+                    proxy
+                        .send_event(UserEvent::RedrawRequested)
+                        .context("Failed to send 'RedrawRequested' event")
                 } else {
                     Err(anyhow!("No match single check menu: {}", id.0))
                 }
@@ -1,7 +1,16 @@
 use super::{MenuGroup, MenuKind, MenuManager};
-use crate::language::LOC;
-use crate::startup::get_startup_status;
-use crate::{config::Config, window::WindowPosition};
+use capsglow::language::LOC;
+use capsglow::monitor::{MonitorId, MonitorSelector};
+use capsglow::osd::DisplayMode;
+use capsglow::palette::PalettePreset;
+use capsglow::platform::{MonitorProvider, WindowsPlatform};
+use capsglow::startup::get_startup_status;
+use capsglow::style::{FontWeight, StyleKind};
+use capsglow::{
+    config::Config,
+    key::LockKey,
+    window::{IndicatorSize, WindowPosition},
+};
 
 use std::sync::LazyLock;
 
@@ -21,13 +30,47 @@ pub static FOLLOW_INDICATOR_AREA_THEME: LazyLock<MenuId> =
     LazyLock::new(|| MenuId::new("follow_indicator_area_theme"));
 pub static FOLLOW_SYSTEM_THEME: LazyLock<MenuId> =
     LazyLock::new(|| MenuId::new("follow_system_theme"));
+pub static FOLLOW_CONTRAST_THEME: LazyLock<MenuId> =
+    LazyLock::new(|| MenuId::new("follow_contrast_theme"));
+pub static FOLLOW_ACCENT_THEME: LazyLock<MenuId> =
+    LazyLock::new(|| MenuId::new("follow_accent_theme"));
+// Palette Preset: GroupSingle
+pub static PALETTE_PRESETS: LazyLock<[(MenuId, PalettePreset, &str); 3]> = LazyLock::new(|| {
+    [
+        (
+            MenuId::new("palette_default"),
+            PalettePreset::Default,
+            LOC.palette_default,
+        ),
+        (
+            MenuId::new("palette_vivid"),
+            PalettePreset::Vivid,
+            LOC.palette_vivid,
+        ),
+        (
+            MenuId::new("palette_soft"),
+            PalettePreset::Soft,
+            LOC.palette_soft,
+        ),
+    ]
+});
 // Monitor GroupSingle: GroupSingle
 pub static SELECT_MOUSE_MONITOR: LazyLock<MenuId> =
     LazyLock::new(|| MenuId::new("select_mouse_monitor"));
 pub static SELECT_PRIMARY_MONITOR: LazyLock<MenuId> =
     LazyLock::new(|| MenuId::new("select_primary_monitor"));
+pub static SELECT_ALL_MONITORS: LazyLock<MenuId> =
+    LazyLock::new(|| MenuId::new("select_all_monitors"));
+/// 指定显示器的菜单 ID 前缀，后接 `MonitorId`
+pub const SELECT_SPECIFIC_MONITOR_PREFIX: &str = "select_monitor:";
+
+pub fn specific_monitor_menu_id(id: &MonitorId) -> MenuId {
+    MenuId::new(format!("{SELECT_SPECIFIC_MONITOR_PREFIX}{}", id.0))
+}
+// Normal
+pub static PLACE_BY_DRAGGING: LazyLock<MenuId> = LazyLock::new(|| MenuId::new("place_by_dragging"));
 // Window Position: GroupSingle
-pub static WINDOW_POSITIONS: LazyLock<[(MenuId, WindowPosition, &str); 9]> = LazyLock::new(|| {
+pub static WINDOW_POSITIONS: LazyLock<[(MenuId, WindowPosition, &str); 12]> = LazyLock::new(|| {
     [
         (
             MenuId::new("position_center"),
@@ -74,8 +117,118 @@ pub static WINDOW_POSITIONS: LazyLock<[(MenuId, WindowPosition, &str); 9]> = Laz
             WindowPosition::BottomRight,
             LOC.position_bottom_right,
         ),
+        (
+            MenuId::new("position_caret"),
+            WindowPosition::Caret,
+            LOC.position_caret,
+        ),
+        (
+            MenuId::new("position_cursor"),
+            WindowPosition::Cursor,
+            LOC.position_cursor,
+        ),
+        (
+            MenuId::new("position_custom"),
+            WindowPosition::Custom,
+            LOC.position_custom,
+        ),
+    ]
+});
+
+// Lock Keys: GroupMulti
+pub static LOCK_KEYS: LazyLock<[(MenuId, LockKey); 3]> = LazyLock::new(|| {
+    [
+        (MenuId::new("lock_key_caps_lock"), LockKey::Caps),
+        (MenuId::new("lock_key_num_lock"), LockKey::Num),
+        (MenuId::new("lock_key_scroll_lock"), LockKey::Scroll),
+    ]
+});
+
+// Indicator Style: GroupSingle
+pub static STYLE_KINDS: LazyLock<[(MenuId, StyleKind, &str); 2]> = LazyLock::new(|| {
+    [
+        (
+            MenuId::new("style_glyph"),
+            StyleKind::Glyph,
+            LOC.style_glyph,
+        ),
+        (MenuId::new("style_text"), StyleKind::Text, LOC.style_text),
+    ]
+});
+// Font Weight: GroupSingle
+pub static FONT_WEIGHTS: LazyLock<[(MenuId, FontWeight, &str); 4]> = LazyLock::new(|| {
+    [
+        (
+            MenuId::new("font_weight_light"),
+            FontWeight::Light,
+            LOC.font_weight_light,
+        ),
+        (
+            MenuId::new("font_weight_regular"),
+            FontWeight::Regular,
+            LOC.font_weight_regular,
+        ),
+        (
+            MenuId::new("font_weight_semibold"),
+            FontWeight::Semibold,
+            LOC.font_weight_semibold,
+        ),
+        (
+            MenuId::new("font_weight_bold"),
+            FontWeight::Bold,
+            LOC.font_weight_bold,
+        ),
     ]
 });
+// Indicator Size: GroupSingle
+pub static INDICATOR_SIZES: LazyLock<[(MenuId, IndicatorSize, &str); 3]> = LazyLock::new(|| {
+    [
+        (
+            MenuId::new("indicator_size_small"),
+            IndicatorSize::SMALL,
+            LOC.size_small,
+        ),
+        (
+            MenuId::new("indicator_size_medium"),
+            IndicatorSize::MEDIUM,
+            LOC.size_medium,
+        ),
+        (
+            MenuId::new("indicator_size_large"),
+            IndicatorSize::LARGE,
+            LOC.size_large,
+        ),
+    ]
+});
+// CheckSingle
+pub static BACKGROUND_PLATE: LazyLock<MenuId> = LazyLock::new(|| MenuId::new("background_plate"));
+
+// Display Mode: GroupSingle
+pub static DISPLAY_MODES: LazyLock<[(MenuId, DisplayMode, &str); 2]> = LazyLock::new(|| {
+    [
+        (
+            MenuId::new("display_persistent"),
+            DisplayMode::Persistent,
+            LOC.display_persistent,
+        ),
+        (
+            MenuId::new("display_osd"),
+            DisplayMode::Osd,
+            LOC.display_osd,
+        ),
+    ]
+});
+// OSD Duration: GroupSingle
+pub static OSD_DURATIONS: LazyLock<[(MenuId, u64); 4]> = LazyLock::new(|| {
+    [
+        (MenuId::new("osd_duration_1000"), 1000),
+        (MenuId::new("osd_duration_1500"), 1500),
+        (MenuId::new("osd_duration_2000"), 2000),
+        (MenuId::new("osd_duration_3000"), 3000),
+    ]
+});
+// CheckSingle
+pub static OSD_SHOW_OFF: LazyLock<MenuId> = LazyLock::new(|| MenuId::new("osd_show_off"));
 
 struct CreateMenuItem(MenuManager);
 
@@ -109,7 +262,7 @@ impl CreateMenuItem {
     }
 
     fn startup(&mut self, text: &str) -> Result<CheckMenuItem> {
-        let should_startup = get_startup_status()?;
+        let should_startup = get_startup_status(&WindowsPlatform)?;
         let menu_id = STARTUP.clone();
         let menu = CheckMenuItem::with_id(menu_id.clone(), text, true, should_startup, None);
         self.0
@@ -134,6 +287,22 @@ impl CreateMenuItem {
             None,
         );
 
+        let menu_follow_contrast_theme = CheckMenuItem::with_id(
+            FOLLOW_CONTRAST_THEME.clone(),
+            LOC.follow_contrast_theme,
+            true,
+            config.is_indicator_contrast_theme(),
+            None,
+        );
+
+        let menu_follow_accent_theme = CheckMenuItem::with_id(
+            FOLLOW_ACCENT_THEME.clone(),
+            LOC.follow_accent_theme,
+            true,
+            config.is_indicator_accent_theme(),
+            None,
+        );
+
         self.0.insert(
             FOLLOW_INDICATOR_AREA_THEME.clone(),
             MenuKind::GroupSingle(
@@ -150,16 +319,58 @@ impl CreateMenuItem {
             ),
             Some(menu_follow_system_theme.clone()),
         );
+        self.0.insert(
+            FOLLOW_CONTRAST_THEME.clone(),
+            MenuKind::GroupSingle(
+                MenuGroup::IndicatorIconTheme,
+                Some(FOLLOW_INDICATOR_AREA_THEME.clone()),
+            ),
+            Some(menu_follow_contrast_theme.clone()),
+        );
+        self.0.insert(
+            FOLLOW_ACCENT_THEME.clone(),
+            MenuKind::GroupSingle(
+                MenuGroup::IndicatorIconTheme,
+                Some(FOLLOW_INDICATOR_AREA_THEME.clone()),
+            ),
+            Some(menu_follow_accent_theme.clone()),
+        );
 
-        Submenu::with_items(
-            LOC.theme,
-            true,
-            &[
-                &menu_follow_indicator_area_theme as &dyn IsMenuItem,
-                &menu_follow_system_theme as &dyn IsMenuItem,
-            ],
-        )
-        .context("Failed to apped 'Indicator Theme' to Tray Menu")
+        let palette = config.get_palette();
+        let palette_items = PALETTE_PRESETS
+            .iter()
+            .map(|(menu_id, preset, text)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    text,
+                    true,
+                    palette.preset == *preset,
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::PalettePreset,
+                        Some(MenuId::new("palette_default")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let menu_separator = Self::separator();
+        let mut theme_refs: Vec<&dyn IsMenuItem> = vec![
+            &menu_follow_indicator_area_theme as &dyn IsMenuItem,
+            &menu_follow_system_theme as &dyn IsMenuItem,
+            &menu_follow_contrast_theme as &dyn IsMenuItem,
+            &menu_follow_accent_theme as &dyn IsMenuItem,
+            &menu_separator as &dyn IsMenuItem,
+        ];
+        theme_refs.extend(palette_items.iter().map(|item| item as &dyn IsMenuItem));
+
+        Submenu::with_items(LOC.theme, true, &theme_refs)
+            .context("Failed to apped 'Indicator Theme' to Tray Menu")
     }
 
     fn window_postion(&mut self, config: &Config) -> Result<Submenu> {
@@ -185,15 +396,247 @@ impl CreateMenuItem {
             })
             .collect::<Vec<CheckMenuItem>>();
 
-        let position_check_refs: Vec<&dyn IsMenuItem> = position_check_items
+        self.0
+            .insert(PLACE_BY_DRAGGING.clone(), MenuKind::Normal, None);
+        let menu_place_by_dragging =
+            MenuItem::with_id(PLACE_BY_DRAGGING.clone(), LOC.place_by_dragging, true, None);
+        let menu_separator = CreateMenuItem::separator();
+
+        let mut position_check_refs: Vec<&dyn IsMenuItem> = position_check_items
             .iter()
             .map(|item| item as &dyn IsMenuItem)
             .collect();
+        position_check_refs.push(&menu_separator as &dyn IsMenuItem);
+        position_check_refs.push(&menu_place_by_dragging as &dyn IsMenuItem);
 
         Submenu::with_items(LOC.position, true, &position_check_refs)
             .context("Failed to apped 'Window Postion' to Tray Menu")
     }
 
+    fn lock_keys(&mut self, config: &Config) -> Result<Submenu> {
+        let lock_key_check_items = LOCK_KEYS
+            .iter()
+            .map(|(menu_id, key)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    key.name(),
+                    true,
+                    config.is_key_enabled(*key),
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupMulti(MenuGroup::LockKey),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let lock_key_check_refs: Vec<&dyn IsMenuItem> = lock_key_check_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+
+        Submenu::with_items(LOC.lock_keys, true, &lock_key_check_refs)
+            .context("Failed to apped 'Lock Keys' to Tray Menu")
+    }
+
+    fn indicator_style(&mut self, config: &Config) -> Result<Submenu> {
+        let style = config.get_indicator_style();
+
+        let style_kind_items = STYLE_KINDS
+            .iter()
+            .map(|(menu_id, kind, text)| {
+                let menu =
+                    CheckMenuItem::with_id(menu_id.clone(), text, true, style.kind == *kind, None);
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::IndicatorStyle,
+                        Some(MenuId::new("style_glyph")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let font_weight_items = FONT_WEIGHTS
+            .iter()
+            .map(|(menu_id, weight, text)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    text,
+                    true,
+                    style.weight == *weight,
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::FontWeight,
+                        Some(MenuId::new("font_weight_regular")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let font_weight_refs: Vec<&dyn IsMenuItem> = font_weight_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+
+        let menu_font_weight = Submenu::with_items(LOC.font_weight, true, &font_weight_refs)
+            .context("Failed to apped 'Font Weight' to Tray Menu")?;
+
+        // 配置文件中的尺寸不是预设值时不勾选任何一项
+        let indicator_size = config.get_indicator_size();
+        let indicator_size_items = INDICATOR_SIZES
+            .iter()
+            .map(|(menu_id, size, text)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    text,
+                    true,
+                    indicator_size == *size,
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::IndicatorSize,
+                        Some(MenuId::new("indicator_size_medium")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let indicator_size_refs: Vec<&dyn IsMenuItem> = indicator_size_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+
+        let menu_indicator_size =
+            Submenu::with_items(LOC.indicator_size, true, &indicator_size_refs)
+                .context("Failed to apped 'Indicator Size' to Tray Menu")?;
+
+        let menu_background_plate = CheckMenuItem::with_id(
+            BACKGROUND_PLATE.clone(),
+            LOC.background_plate,
+            true,
+            style.background_plate,
+            None,
+        );
+        self.0.insert(
+            BACKGROUND_PLATE.clone(),
+            MenuKind::CheckSingle,
+            Some(menu_background_plate.clone()),
+        );
+
+        let menu_separator = Self::separator();
+        let mut style_refs: Vec<&dyn IsMenuItem> = style_kind_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+        style_refs.extend([
+            &menu_separator as &dyn IsMenuItem,
+            &menu_font_weight as &dyn IsMenuItem,
+            &menu_indicator_size as &dyn IsMenuItem,
+            &menu_background_plate as &dyn IsMenuItem,
+        ]);
+
+        Submenu::with_items(LOC.style, true, &style_refs)
+            .context("Failed to apped 'Indicator Style' to Tray Menu")
+    }
+
+    fn display_mode(&mut self, config: &Config) -> Result<Submenu> {
+        let display = config.get_display_setting();
+
+        let display_mode_items = DISPLAY_MODES
+            .iter()
+            .map(|(menu_id, mode, text)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    text,
+                    true,
+                    display.mode == *mode,
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::DisplayMode,
+                        Some(MenuId::new("display_persistent")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let osd_duration_items = OSD_DURATIONS
+            .iter()
+            .map(|(menu_id, duration_ms)| {
+                let menu = CheckMenuItem::with_id(
+                    menu_id.clone(),
+                    format!("{} s", *duration_ms as f64 / 1000.0),
+                    true,
+                    display.osd_duration_ms == *duration_ms,
+                    None,
+                );
+                self.0.insert(
+                    menu_id.clone(),
+                    MenuKind::GroupSingle(
+                        MenuGroup::OsdDuration,
+                        Some(MenuId::new("osd_duration_1500")),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let osd_duration_refs: Vec<&dyn IsMenuItem> = osd_duration_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+
+        let menu_osd_duration = Submenu::with_items(LOC.osd_duration, true, &osd_duration_refs)
+            .context("Failed to apped 'OSD Duration' to Tray Menu")?;
+
+        let menu_osd_show_off = CheckMenuItem::with_id(
+            OSD_SHOW_OFF.clone(),
+            LOC.osd_show_off,
+            true,
+            display.osd_show_off,
+            None,
+        );
+        self.0.insert(
+            OSD_SHOW_OFF.clone(),
+            MenuKind::CheckSingle,
+            Some(menu_osd_show_off.clone()),
+        );
+
+        let menu_separator = Self::separator();
+        let mut display_refs: Vec<&dyn IsMenuItem> = display_mode_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect();
+        display_refs.extend([
+            &menu_separator as &dyn IsMenuItem,
+            &menu_osd_duration as &dyn IsMenuItem,
+            &menu_osd_show_off as &dyn IsMenuItem,
+        ]);
+
+        Submenu::with_items(LOC.display_mode, true, &display_refs)
+            .context("Failed to apped 'Display Mode' to Tray Menu")
+    }
+
     fn select_monitor(&mut self, config: &Config) -> Result<Submenu> {
         let menu_select_primary_monitor = CheckMenuItem::with_id(
             SELECT_PRIMARY_MONITOR.clone(),
@@ -228,15 +671,85 @@ impl CreateMenuItem {
             Some(menu_select_mouse_monitor.clone()),
         );
 
-        Submenu::with_items(
-            LOC.select_monitor,
+        let menu_select_all_monitors = CheckMenuItem::with_id(
+            SELECT_ALL_MONITORS.clone(),
+            LOC.select_all_monitors,
             true,
-            &[
-                &menu_select_primary_monitor as &dyn IsMenuItem,
-                &menu_select_mouse_monitor as &dyn IsMenuItem,
-            ],
-        )
-        .context("Failed to apped 'Select Monitor' to Tray Menu")
+            config.is_all_monitors(),
+            None,
+        );
+        self.0.insert(
+            SELECT_ALL_MONITORS.clone(),
+            MenuKind::GroupSingle(
+                MenuGroup::MonitorSelector,
+                Some(SELECT_MOUSE_MONITOR.clone()),
+            ),
+            Some(menu_select_all_monitors.clone()),
+        );
+
+        // 当前连接的显示器，显示器变化时重新创建菜单
+        let pinned = match config.get_monitor_selector() {
+            MonitorSelector::Specific(id) => Some(id),
+            _ => None,
+        };
+        let monitors = WindowsPlatform.monitors();
+        let mut specific_items = monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| {
+                let checked = pinned.as_ref() == Some(&monitor.id);
+                (
+                    specific_monitor_menu_id(&monitor.id),
+                    monitor.label(index),
+                    true,
+                    checked,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // 固定的显示器已断开时仍显示为选中，窗口暂时回退到主屏幕
+        if let Some(id) = pinned.filter(|id| monitors.iter().all(|monitor| monitor.id != *id)) {
+            specific_items.push((
+                specific_monitor_menu_id(&id),
+                LOC.monitor_disconnected.to_owned(),
+                false,
+                true,
+            ));
+        }
+
+        let specific_check_items = specific_items
+            .into_iter()
+            .map(|(menu_id, text, enabled, checked)| {
+                let menu = CheckMenuItem::with_id(menu_id.clone(), text, enabled, checked, None);
+                self.0.insert(
+                    menu_id,
+                    MenuKind::GroupSingle(
+                        MenuGroup::MonitorSelector,
+                        Some(SELECT_MOUSE_MONITOR.clone()),
+                    ),
+                    Some(menu.clone()),
+                );
+                menu
+            })
+            .collect::<Vec<CheckMenuItem>>();
+
+        let menu_separator = CreateMenuItem::separator();
+        let mut monitor_refs: Vec<&dyn IsMenuItem> = vec![
+            &menu_select_primary_monitor as &dyn IsMenuItem,
+            &menu_select_mouse_monitor as &dyn IsMenuItem,
+            &menu_select_all_monitors as &dyn IsMenuItem,
+        ];
+        if !specific_check_items.is_empty() {
+            monitor_refs.push(&menu_separator as &dyn IsMenuItem);
+            monitor_refs.extend(
+                specific_check_items
+                    .iter()
+                    .map(|item| item as &dyn IsMenuItem),
+            );
+        }
+
+        Submenu::with_items(LOC.select_monitor, true, &monitor_refs)
+            .context("Failed to apped 'Select Monitor' to Tray Menu")
     }
 }
 
@@ -257,12 +770,21 @@ pub fn create_menu(config: &Config) -> Result<(Menu, MenuManager)> {
 
     let menu_indicator_theme = create_menu_item.indicator_theme(config)?;
 
+    let menu_indicator_style = create_menu_item.indicator_style(config)?;
+
+    let menu_display_mode = create_menu_item.display_mode(config)?;
+
     let menu_window_position = create_menu_item.window_postion(config)?;
 
     let menu_select_monitor = create_menu_item.select_monitor(config)?;
 
+    let menu_lock_keys = create_menu_item.lock_keys(config)?;
+
     let tray_menu = Menu::new();
 
+    tray_menu
+        .append(&menu_lock_keys)
+        .context("Failed to apped 'Lock Keys' to Tray Menu")?;
     tray_menu
         .append(&menu_select_monitor)
         .context("Failed to apped 'Select Monitor up' to Tray Menu")?;
@@ -272,6 +794,12 @@ pub fn create_menu(config: &Config) -> Result<(Menu, MenuManager)> {
     tray_menu
         .append(&menu_indicator_theme)
         .context("Failed to apped 'Indicator Theme' to Tray Menu")?;
+    tray_menu
+        .append(&menu_indicator_style)
+        .context("Failed to apped 'Indicator Style' to Tray Menu")?;
+    tray_menu
+        .append(&menu_display_mode)
+        .context("Failed to apped 'Display Mode' to Tray Menu")?;
     tray_menu
         .append(&menu_separator)
         .context("Failed to apped 'Separator' to Tray Menu")?;
@@ -11,11 +11,18 @@ use tray_icon::menu::{CheckMenuItem, MenuId};
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MenuGroup {
     // GroupMulti
+    LockKey,
     // ... add more groups here as needed
     // GroupSingle
     WindowPosition,
     MonitorSelector,
     IndicatorIconTheme,
+    PalettePreset,
+    IndicatorStyle,
+    FontWeight,
+    IndicatorSize,
+    DisplayMode,
+    OsdDuration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1,10 +1,7 @@
 pub mod menu;
 
-use crate::{
-    config::Config,
-    icon::load_icon_for_tray,
-    tray::menu::{MenuManager, item::create_menu},
-};
+use crate::tray::menu::{MenuManager, item::create_menu};
+use capsglow::{config::Config, icon::load_icon_for_tray};
 
 use anyhow::{Result, anyhow};
 use tray_icon::{TrayIcon, TrayIconBuilder};
@@ -3,3 +3,9 @@ use std::os::windows::ffi::OsStrExt;
 pub fn to_wide<S: AsRef<std::ffi::OsStr>>(s: S) -> Vec<u16> {
     s.as_ref().encode_wide().chain(std::iter::once(0)).collect()
 }
+
+/// 读取以 0 结尾的宽字符缓冲区
+pub fn from_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
@@ -1,13 +1,26 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use winit::dpi::PhysicalPosition;
 
+use crate::config::WINDOW_LOGICAL_SIZE;
 use crate::monitor::MonitorSelector;
+use crate::platform::{MonitorArea, MonitorProvider, Point, Rect};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WindowSetting {
     pub position: WindowPosition,
     pub monitor_selector: MonitorSelector,
+    #[serde(default)]
+    pub anchor_area: AnchorArea,
+    #[serde(default)]
+    pub offset: WindowOffset,
+    #[serde(default)]
+    pub follow: FollowSetting,
+    #[serde(default)]
+    pub custom: CustomPosition,
+    #[serde(default)]
+    pub size: IndicatorSize,
 }
 
 impl Default for WindowSetting {
@@ -15,42 +28,396 @@ impl Default for WindowSetting {
         Self {
             position: WindowPosition::Center,
             monitor_selector: MonitorSelector::MouseMonitor,
+            anchor_area: AnchorArea::default(),
+            offset: WindowOffset::default(),
+            follow: FollowSetting::default(),
+            custom: CustomPosition::default(),
+            size: IndicatorSize::default(),
         }
     }
 }
 
+/// 指示器的边长（逻辑像素），按窗口所在显示器的缩放比例换算为物理像素
+///
+/// 自定义图标按此尺寸重新采样，窗口的长边等于该边长
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IndicatorSize(pub f64);
+
+impl Default for IndicatorSize {
+    fn default() -> Self {
+        Self::MEDIUM
+    }
+}
+
+impl IndicatorSize {
+    pub const SMALL: Self = Self(120.0);
+    pub const MEDIUM: Self = Self(WINDOW_LOGICAL_SIZE);
+    pub const LARGE: Self = Self(300.0);
+
+    const MIN: f64 = 16.0;
+    const MAX: f64 = 1024.0;
+
+    /// 限制在合理范围内的逻辑边长，配置文件中的无效值使用默认尺寸
+    pub fn logical(&self) -> f64 {
+        if self.0.is_finite() {
+            self.0.clamp(Self::MIN, Self::MAX)
+        } else {
+            WINDOW_LOGICAL_SIZE
+        }
+    }
+
+    /// 缩放比例为 `scale` 的显示器上的物理边长
+    pub fn to_phy(&self, scale: f64) -> u32 {
+        ((self.logical() * scale).round() as u32).max(1)
+    }
+}
+
+/// 拖动放置（`WindowPosition::Custom`）的位置：窗口中心在显示器（或工作区）中的相对坐标
+///
+/// 按比例保存，分辨率或缩放比例变化后窗口仍在相同的相对位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomPosition {
+    /// 0 为左边缘，1 为右边缘
+    pub x: f64,
+    /// 0 为上边缘，1 为下边缘
+    pub y: f64,
+}
+
+impl Default for CustomPosition {
+    fn default() -> Self {
+        Self { x: 0.5, y: 0.5 }
+    }
+}
+
+/// 跟随文字光标（`WindowPosition::Caret`）或鼠标（`WindowPosition::Cursor`）时的设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FollowSetting {
+    /// 窗口相对光标左下角的水平偏移（逻辑像素）
+    pub offset_x: f64,
+    /// 窗口相对光标左下角的垂直偏移（逻辑像素），下方放不下时改为放在光标上方
+    pub offset_y: f64,
+    /// 跟随鼠标时更新窗口位置的最短间隔（毫秒）
+    pub interval_ms: u64,
+    /// 既找不到文字光标也无法获取鼠标位置时使用的锚点
+    pub fallback: WindowPosition,
+}
+
+impl Default for FollowSetting {
+    fn default() -> Self {
+        Self {
+            offset_x: 8.0,
+            offset_y: 8.0,
+            interval_ms: 33,
+            fallback: WindowPosition::Center,
+        }
+    }
+}
+
+impl FollowSetting {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.max(1))
+    }
+}
+
+/// 锚点所参照的区域
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AnchorArea {
+    /// 整个显示器，底部等位置可能与任务栏重叠
+    #[default]
+    Monitor,
+    /// 显示器的工作区，避开任务栏
+    WorkArea,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OffsetUnit {
+    /// 逻辑像素，按显示器的缩放比例换算为物理像素
+    #[default]
+    Logical,
+    /// 显示器宽度（水平方向）或高度（垂直方向）的百分比
+    Percent,
+}
+
+/// 在九个锚点之上的边距与偏移
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowOffset {
+    /// 锚定前将显示器四周向内收缩的距离，使窗口不紧贴屏幕边缘
+    pub margin: f64,
+    /// 锚定后的水平偏移，正值向右
+    pub x: f64,
+    /// 锚定后的垂直偏移，正值向下
+    pub y: f64,
+    pub unit: OffsetUnit,
+}
+
+impl WindowOffset {
+    /// 将 `value` 换算为物理像素，`monitor_length` 为对应方向上显示器的物理长度
+    fn to_phy(&self, value: f64, monitor_length: i32, scale: f64) -> i32 {
+        match self.unit {
+            OffsetUnit::Logical => logical_to_phy(value, scale),
+            OffsetUnit::Percent => (value / 100.0 * monitor_length as f64).round() as i32,
+        }
+    }
+
+    /// 按边距收缩后的显示器矩形
+    fn inset(&self, rect: Rect, scale: f64) -> Rect {
+        let margin_x = self.to_phy(self.margin, rect.width(), scale);
+        let margin_y = self.to_phy(self.margin, rect.height(), scale);
+        Rect::new(
+            rect.left + margin_x,
+            rect.top + margin_y,
+            rect.right - margin_x,
+            rect.bottom - margin_y,
+        )
+    }
+
+    /// 锚定后的物理偏移 `(x, y)`
+    fn phy_offset(&self, rect: Rect, scale: f64) -> (i32, i32) {
+        (
+            self.to_phy(self.x, rect.width(), scale),
+            self.to_phy(self.y, rect.height(), scale),
+        )
+    }
+}
+
 impl WindowSetting {
     pub fn get_phy_position(
         &self,
+        monitors: &impl MonitorProvider,
         window_phy_width: u32,
         window_phy_height: u32,
-    ) -> Result<PhysicalPosition<i32>> {
-        let rect = self.monitor_selector.get_target_monitor_phy_rect()?;
-        let (m_left, m_right, m_top, m_bottom) = (rect.left, rect.right, rect.top, rect.bottom);
+    ) -> Result<Point> {
+        match self.follow_target(monitors) {
+            Some(target) => {
+                self.get_follow_phy_position(monitors, target, window_phy_width, window_phy_height)
+            }
+            None => self.get_anchor_phy_position(
+                monitors,
+                self.anchor_position(),
+                window_phy_width,
+                window_phy_height,
+            ),
+        }
+    }
+
+    /// 窗口将要显示的显示器的缩放比例，窗口大小应按此计算
+    pub fn get_target_scale_factor(&self, monitors: &impl MonitorProvider) -> Result<f64> {
+        let point = match self.follow_target(monitors) {
+            Some(target) => Point::new(target.left, target.top),
+            None => {
+                let area = self
+                    .monitor_selector
+                    .get_target_monitor_phy_area(monitors)?;
+                let rect = self.select_area(area);
+                Point::new(rect.left, rect.top)
+            }
+        };
+
+        Ok(monitors.monitor_scale_factor(point))
+    }
+
+    /// 跟随模式下窗口要靠近的矩形：文字光标 -> 鼠标，都找不到时使用备用锚点（返回 `None`）
+    fn follow_target(&self, monitors: &impl MonitorProvider) -> Option<Rect> {
+        let cursor_rect = || {
+            monitors
+                .cursor_position()
+                .ok()
+                .map(|cursor| Rect::new(cursor.x, cursor.y, cursor.x, cursor.y))
+        };
+
+        match self.position {
+            WindowPosition::Caret => monitors.caret_rect().or_else(cursor_rect),
+            WindowPosition::Cursor => cursor_rect(),
+            _ => None,
+        }
+    }
+
+    /// 不跟随光标时使用的锚点，跟随模式下为备用锚点
+    fn anchor_position(&self) -> &WindowPosition {
+        match self.position.follows_input() {
+            true => &self.follow.fallback,
+            false => &self.position,
+        }
+    }
+
+    fn select_area(&self, area: MonitorArea) -> Rect {
+        match self.anchor_area {
+            AnchorArea::Monitor => area.monitor,
+            AnchorArea::WorkArea => area.work,
+        }
+    }
+
+    /// 将窗口放在 `target` 的左下方，下方放不下时放在上方，并限制在 `target` 所在的显示器内
+    fn get_follow_phy_position(
+        &self,
+        monitors: &impl MonitorProvider,
+        target: Rect,
+        window_phy_width: u32,
+        window_phy_height: u32,
+    ) -> Result<Point> {
+        let target_point = Point::new(target.left, target.top);
+        let rect = self.select_area(monitors.monitor_area_from_point(target_point)?);
+        let scale = monitors.monitor_scale_factor(target_point);
         let (w_width, w_height) = (window_phy_width as i32, window_phy_height as i32);
-        let position = &self.position;
+        let offset_x = logical_to_phy(self.follow.offset_x, scale);
+        let offset_y = logical_to_phy(self.follow.offset_y, scale);
+
+        let x = target.left + offset_x;
+        let y = if target.bottom + offset_y + w_height <= rect.bottom {
+            target.bottom + offset_y
+        } else {
+            target.top - offset_y - w_height
+        };
+
+        Ok(clamp_to_rect(Point::new(x, y), rect, w_width, w_height))
+    }
+
+    fn get_anchor_phy_position(
+        &self,
+        monitors: &impl MonitorProvider,
+        position: &WindowPosition,
+        window_phy_width: u32,
+        window_phy_height: u32,
+    ) -> Result<Point> {
+        let area = self
+            .monitor_selector
+            .get_target_monitor_phy_area(monitors)?;
+        let monitor_rect = self.select_area(area);
+        let scale = monitors.monitor_scale_factor(Point::new(monitor_rect.left, monitor_rect.top));
+
+        Ok(self.anchor_in_area(area, scale, position, window_phy_width, window_phy_height))
+    }
+
+    /// 在所有显示器上同时显示时，窗口在 `area` 所在显示器中的位置
+    ///
+    /// 每个显示器各有一个窗口，无法跟随光标，因此跟随模式使用备用锚点
+    pub fn get_mirror_phy_position(
+        &self,
+        area: MonitorArea,
+        scale: f64,
+        window_phy_width: u32,
+        window_phy_height: u32,
+    ) -> Point {
+        self.anchor_in_area(
+            area,
+            scale,
+            self.anchor_position(),
+            window_phy_width,
+            window_phy_height,
+        )
+    }
+
+    fn anchor_in_area(
+        &self,
+        area: MonitorArea,
+        scale: f64,
+        position: &WindowPosition,
+        window_phy_width: u32,
+        window_phy_height: u32,
+    ) -> Point {
+        let monitor_rect = self.select_area(area);
+        let (w_width, w_height) = (window_phy_width as i32, window_phy_height as i32);
+
+        // 拖动放置的位置已包含用户想要的边距，不再叠加边距与偏移
+        if *position == WindowPosition::Custom {
+            let center_x = monitor_rect.left + (self.custom.x * monitor_rect.width() as f64) as i32;
+            let center_y = monitor_rect.top + (self.custom.y * monitor_rect.height() as f64) as i32;
+            let point = Point::new(center_x - w_width / 2, center_y - w_height / 2);
+            return clamp_to_rect(point, monitor_rect, w_width, w_height);
+        }
+
+        let rect = self.offset.inset(monitor_rect, scale);
+        let (m_left, m_right, m_top, m_bottom) = (rect.left, rect.right, rect.top, rect.bottom);
 
         let (x, y) = match position {
-            WindowPosition::Center => (
+            // 备用锚点不能再是跟随模式
+            WindowPosition::Center
+            | WindowPosition::Caret
+            | WindowPosition::Cursor
+            | WindowPosition::Custom => (
                 ((m_left + m_right - w_width) / 2),
                 (m_top + m_bottom - w_height) / 2,
             ),
             WindowPosition::Left => (m_left, (m_top + m_bottom - w_height) / 2),
             WindowPosition::Right => ((m_right - w_width), (m_top + m_bottom - w_height) / 2),
             WindowPosition::Top => ((m_left + m_right - w_width) / 2, m_top),
-            WindowPosition::Bottom => (
-                (m_left + m_right - w_width) / 2,
-                (m_top + m_bottom - w_height),
-            ),
+            WindowPosition::Bottom => ((m_left + m_right - w_width) / 2, (m_bottom - w_height)),
             WindowPosition::TopLeft => (m_left, m_top),
             WindowPosition::TopRight => ((m_right - w_width), m_top),
-            WindowPosition::BottomLeft => (m_left, (m_top + m_bottom - w_height)),
-            WindowPosition::BottomRight => ((m_right - w_width), (m_top + m_bottom - w_height)),
+            WindowPosition::BottomLeft => (m_left, (m_bottom - w_height)),
+            WindowPosition::BottomRight => ((m_right - w_width), (m_bottom - w_height)),
         };
-        Ok(PhysicalPosition::new(x, y))
+
+        let (offset_x, offset_y) = self.offset.phy_offset(monitor_rect, scale);
+        let point = Point::new(x + offset_x, y + offset_y);
+        clamp_to_rect(point, monitor_rect, w_width, w_height)
+    }
+
+    /// 记录拖动放置后窗口 `window_rect` 的位置，并切换到 `WindowPosition::Custom`
+    ///
+    /// 窗口被拖到当前目标以外的显示器时，改为固定在该显示器上；在所有显示器上显示时保持不变，
+    /// 相对位置按拖动的窗口所在的显示器计算
+    pub fn set_custom_position(
+        &mut self,
+        monitors: &impl MonitorProvider,
+        window_rect: Rect,
+    ) -> Result<()> {
+        let center = Point::new(
+            (window_rect.left + window_rect.right) / 2,
+            (window_rect.top + window_rect.bottom) / 2,
+        );
+        let area = monitors.monitor_area_from_point(center)?;
+
+        if self.monitor_selector != MonitorSelector::AllMonitors
+            && self
+                .monitor_selector
+                .get_target_monitor_phy_area(monitors)?
+                != area
+            && let Some(monitor) = monitors
+                .monitors()
+                .into_iter()
+                .find(|monitor| monitor.area == area)
+        {
+            self.monitor_selector = MonitorSelector::Specific(monitor.id);
+        }
+
+        let rect = self.select_area(area);
+        let fraction = |value: i32, start: i32, length: i32| {
+            ((value - start) as f64 / length.max(1) as f64).clamp(0.0, 1.0)
+        };
+        self.custom = CustomPosition {
+            x: fraction(center.x, rect.left, rect.width()),
+            y: fraction(center.y, rect.top, rect.height()),
+        };
+        self.position = WindowPosition::Custom;
+
+        Ok(())
     }
 }
 
+impl WindowPosition {
+    /// 是否在每次显示时移动到输入位置附近
+    pub fn follows_input(&self) -> bool {
+        matches!(self, WindowPosition::Caret | WindowPosition::Cursor)
+    }
+}
+
+fn logical_to_phy(value: f64, scale: f64) -> i32 {
+    (value * scale).round() as i32
+}
+
+/// 移动 `point`，使 `width × height` 的窗口完整位于 `rect` 内（窗口比 `rect` 大时对齐左上角）
+fn clamp_to_rect(point: Point, rect: Rect, width: i32, height: i32) -> Point {
+    Point::new(
+        point.x.min(rect.right - width).max(rect.left),
+        point.y.min(rect.bottom - height).max(rect.top),
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WindowPosition {
     Center,
@@ -62,4 +429,439 @@ pub enum WindowPosition {
     TopRight,
     BottomLeft,
     BottomRight,
+    /// 跟随当前输入位置的文字光标
+    Caret,
+    /// 跟随鼠标指针
+    Cursor,
+    /// 拖动放置的位置，见 `CustomPosition`
+    Custom,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::icon::indicator_window_size;
+    use crate::platform::fake::FakePlatform;
+
+    fn setting(position: WindowPosition, monitor_selector: MonitorSelector) -> WindowSetting {
+        WindowSetting {
+            position,
+            monitor_selector,
+            anchor_area: AnchorArea::default(),
+            offset: WindowOffset::default(),
+            follow: FollowSetting::default(),
+            custom: CustomPosition::default(),
+            size: IndicatorSize::default(),
+        }
+    }
+
+    #[test]
+    fn anchors_on_primary_monitor() {
+        let platform = FakePlatform::default();
+        let position = |position| {
+            setting(position, MonitorSelector::PrimaryMonitor)
+                .get_phy_position(&platform, 200, 100)
+                .unwrap()
+        };
+
+        assert_eq!(position(WindowPosition::Center), Point::new(860, 490));
+        assert_eq!(position(WindowPosition::TopLeft), Point::new(0, 0));
+        assert_eq!(position(WindowPosition::Right), Point::new(1720, 490));
+        assert_eq!(position(WindowPosition::BottomRight), Point::new(1720, 980));
+    }
+
+    #[test]
+    fn mouse_monitor_follows_the_cursor() {
+        let platform = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(-1280, 0, 0, 1024),
+        ]);
+        platform.cursor.set(Point::new(-100, 500));
+
+        let position = setting(WindowPosition::TopLeft, MonitorSelector::MouseMonitor)
+            .get_phy_position(&platform, 200, 200)
+            .unwrap();
+
+        assert_eq!(position, Point::new(-1280, 0));
+    }
+
+    #[test]
+    fn margin_and_offset_are_scaled_by_the_monitor_dpi() {
+        let platform = FakePlatform {
+            scale_factor: 1.5,
+            ..Default::default()
+        };
+        let position = |position, offset| {
+            WindowSetting {
+                offset,
+                ..setting(position, MonitorSelector::PrimaryMonitor)
+            }
+            .get_phy_position(&platform, 200, 100)
+            .unwrap()
+        };
+
+        let margin = WindowOffset {
+            margin: 20.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            position(WindowPosition::TopLeft, margin.clone()),
+            Point::new(30, 30)
+        );
+        assert_eq!(
+            position(WindowPosition::BottomRight, margin.clone()),
+            Point::new(1690, 950)
+        );
+        assert_eq!(
+            position(WindowPosition::Center, margin),
+            Point::new(860, 490)
+        );
+
+        let offset = WindowOffset {
+            x: 10.0,
+            y: -40.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            position(WindowPosition::Bottom, offset),
+            Point::new(875, 920)
+        );
+    }
+
+    #[test]
+    fn percent_offset_follows_the_monitor_size() {
+        let platform = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3200, 1000),
+        ]);
+        platform.cursor.set(Point::new(2000, 500));
+
+        let position = WindowSetting {
+            offset: WindowOffset {
+                margin: 5.0,
+                x: -10.0,
+                y: 10.0,
+                unit: OffsetUnit::Percent,
+            },
+            ..setting(WindowPosition::TopRight, MonitorSelector::MouseMonitor)
+        }
+        .get_phy_position(&platform, 100, 100)
+        .unwrap();
+
+        // 右边距 64、上边距 50，再偏移 (-128, 100)
+        assert_eq!(position, Point::new(3200 - 64 - 100 - 128, 50 + 100));
+    }
+
+    #[test]
+    fn work_area_keeps_every_anchor_clear_of_the_taskbar() {
+        // 任务栏在底部，高 48
+        let platform = FakePlatform::with_monitor_areas(vec![MonitorArea {
+            monitor: Rect::new(0, 0, 1920, 1080),
+            work: Rect::new(0, 0, 1920, 1032),
+        }]);
+        let position = |position| {
+            WindowSetting {
+                anchor_area: AnchorArea::WorkArea,
+                ..setting(position, MonitorSelector::PrimaryMonitor)
+            }
+            .get_phy_position(&platform, 200, 100)
+            .unwrap()
+        };
+
+        let expected = [
+            (WindowPosition::Center, Point::new(860, 466)),
+            (WindowPosition::Left, Point::new(0, 466)),
+            (WindowPosition::Right, Point::new(1720, 466)),
+            (WindowPosition::Top, Point::new(860, 0)),
+            (WindowPosition::Bottom, Point::new(860, 932)),
+            (WindowPosition::TopLeft, Point::new(0, 0)),
+            (WindowPosition::TopRight, Point::new(1720, 0)),
+            (WindowPosition::BottomLeft, Point::new(0, 932)),
+            (WindowPosition::BottomRight, Point::new(1720, 932)),
+        ];
+        for (anchor, point) in expected {
+            assert_eq!(position(anchor.clone()), point, "{anchor:?}");
+        }
+
+        // 默认参照整个显示器
+        assert_eq!(
+            setting(WindowPosition::BottomRight, MonitorSelector::PrimaryMonitor)
+                .get_phy_position(&platform, 200, 100)
+                .unwrap(),
+            Point::new(1720, 980)
+        );
+    }
+
+    #[test]
+    fn work_area_on_a_left_taskbar_monitor() {
+        let platform = FakePlatform::with_monitor_areas(vec![
+            MonitorArea::new(Rect::new(0, 0, 1920, 1080)),
+            MonitorArea {
+                monitor: Rect::new(1920, -200, 3200, 824),
+                work: Rect::new(1982, -200, 3200, 824),
+            },
+        ]);
+        platform.cursor.set(Point::new(2500, 0));
+
+        let position = |position| {
+            WindowSetting {
+                anchor_area: AnchorArea::WorkArea,
+                ..setting(position, MonitorSelector::MouseMonitor)
+            }
+            .get_phy_position(&platform, 100, 100)
+            .unwrap()
+        };
+
+        assert_eq!(position(WindowPosition::TopLeft), Point::new(1982, -200));
+        assert_eq!(position(WindowPosition::BottomLeft), Point::new(1982, 724));
+        assert_eq!(position(WindowPosition::Center), Point::new(2541, 262));
+    }
+
+    #[test]
+    fn caret_places_the_window_below_the_text_cursor() {
+        let platform = FakePlatform {
+            scale_factor: 2.0,
+            ..Default::default()
+        };
+        let position = || {
+            setting(WindowPosition::Caret, MonitorSelector::PrimaryMonitor)
+                .get_phy_position(&platform, 200, 100)
+                .unwrap()
+        };
+
+        platform.caret.set(Some(Rect::new(400, 300, 402, 330)));
+        assert_eq!(position(), Point::new(416, 346));
+
+        // 下方放不下时放在光标上方
+        platform.caret.set(Some(Rect::new(400, 1000, 402, 1030)));
+        assert_eq!(position(), Point::new(416, 884));
+
+        // 靠近右边缘时限制在显示器内
+        platform.caret.set(Some(Rect::new(1900, 300, 1902, 330)));
+        assert_eq!(position(), Point::new(1720, 346));
+    }
+
+    #[test]
+    fn caret_falls_back_to_the_mouse_cursor() {
+        let platform = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 3840, 1080),
+        ]);
+        platform.cursor.set(Point::new(3000, 500));
+
+        let position = setting(WindowPosition::Caret, MonitorSelector::PrimaryMonitor)
+            .get_phy_position(&platform, 200, 100)
+            .unwrap();
+
+        assert_eq!(position, Point::new(3008, 508));
+    }
+
+    #[test]
+    fn cursor_mode_tracks_the_pointer_and_stays_on_screen() {
+        let platform = FakePlatform::default();
+        // 即使存在文字光标也只跟随鼠标
+        platform.caret.set(Some(Rect::new(400, 300, 402, 330)));
+        let position = |cursor| {
+            platform.cursor.set(cursor);
+            setting(WindowPosition::Cursor, MonitorSelector::PrimaryMonitor)
+                .get_phy_position(&platform, 200, 100)
+                .unwrap()
+        };
+
+        assert_eq!(position(Point::new(100, 100)), Point::new(108, 108));
+        assert_eq!(position(Point::new(1900, 500)), Point::new(1720, 508));
+        assert_eq!(position(Point::new(1000, 1075)), Point::new(1008, 967));
+    }
+
+    #[test]
+    fn anchor_offsets_cannot_push_the_window_off_screen() {
+        let platform = FakePlatform::default();
+        let position = WindowSetting {
+            offset: WindowOffset {
+                x: 500.0,
+                y: -500.0,
+                ..Default::default()
+            },
+            ..setting(WindowPosition::TopRight, MonitorSelector::PrimaryMonitor)
+        }
+        .get_phy_position(&platform, 200, 100)
+        .unwrap();
+
+        assert_eq!(position, Point::new(1720, 0));
+    }
+
+    #[test]
+    fn mirrors_use_each_monitor_dpi_and_the_fallback_anchor() {
+        let setting = WindowSetting {
+            offset: WindowOffset {
+                margin: 10.0,
+                ..Default::default()
+            },
+            follow: FollowSetting {
+                fallback: WindowPosition::BottomRight,
+                ..Default::default()
+            },
+            ..setting(WindowPosition::Caret, MonitorSelector::AllMonitors)
+        };
+        let secondary = MonitorArea::new(Rect::new(1920, 0, 4480, 1440));
+
+        assert_eq!(
+            setting.get_mirror_phy_position(secondary, 2.0, 256, 256),
+            Point::new(4480 - 20 - 256, 1440 - 20 - 256)
+        );
+
+        let setting = WindowSetting {
+            position: WindowPosition::Top,
+            ..setting
+        };
+        assert_eq!(
+            setting.get_mirror_phy_position(secondary, 1.0, 128, 128),
+            Point::new(1920 + (2560 - 128) / 2, 10)
+        );
+    }
+
+    #[test]
+    fn window_size_follows_the_target_monitor_dpi() {
+        let platform = FakePlatform {
+            monitor_scale_factors: vec![1.0, 1.5],
+            scale_factor: 2.0,
+            ..FakePlatform::with_monitors(vec![
+                Rect::new(0, 0, 1920, 1080),
+                Rect::new(1920, 0, 4480, 1440),
+            ])
+        };
+        platform.cursor.set(Point::new(2500, 700));
+        let window_size = |setting: WindowSetting| {
+            let scale = setting.get_target_scale_factor(&platform).unwrap();
+            indicator_window_size(&HashMap::new(), IndicatorSize::default(), scale)
+        };
+        let size = |scale: f64| {
+            let size = (WINDOW_LOGICAL_SIZE * scale).round() as u32;
+            (size, size)
+        };
+
+        assert_eq!(
+            window_size(setting(
+                WindowPosition::Center,
+                MonitorSelector::MouseMonitor
+            )),
+            size(1.5)
+        );
+        assert_eq!(
+            window_size(setting(
+                WindowPosition::Center,
+                MonitorSelector::PrimaryMonitor
+            )),
+            size(1.0)
+        );
+
+        // 跟随文字光标时按光标所在的显示器计算
+        platform.caret.set(Some(Rect::new(100, 100, 102, 120)));
+        assert_eq!(
+            window_size(setting(
+                WindowPosition::Caret,
+                MonitorSelector::MouseMonitor
+            )),
+            size(1.0)
+        );
+        assert_eq!(
+            window_size(setting(
+                WindowPosition::Cursor,
+                MonitorSelector::PrimaryMonitor
+            )),
+            size(1.5)
+        );
+    }
+
+    #[test]
+    fn unplugging_a_monitor_moves_the_window_to_a_connected_one() {
+        let setting = setting(WindowPosition::BottomRight, MonitorSelector::MouseMonitor);
+        let docked = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 4480, 1440),
+        ]);
+        docked.cursor.set(Point::new(3000, 1200));
+        assert_eq!(
+            setting.get_phy_position(&docked, 200, 100).unwrap(),
+            Point::new(4280, 1340)
+        );
+
+        // 鼠标仍停留在已断开的显示器的坐标上
+        let undocked = FakePlatform::default();
+        undocked.cursor.set(Point::new(3000, 1200));
+        assert_eq!(
+            setting.get_phy_position(&undocked, 200, 100).unwrap(),
+            Point::new(1720, 980)
+        );
+    }
+
+    #[test]
+    fn dragged_position_is_kept_relative_to_the_monitor() {
+        let mut setting = setting(WindowPosition::Center, MonitorSelector::PrimaryMonitor);
+        let platform = FakePlatform::default();
+
+        setting
+            .set_custom_position(&platform, Rect::new(1400, 200, 1600, 300))
+            .unwrap();
+        assert_eq!(setting.position, WindowPosition::Custom);
+        assert_eq!(
+            setting.get_phy_position(&platform, 200, 100).unwrap(),
+            Point::new(1400, 200)
+        );
+
+        // 分辨率变化后保持相同的相对位置
+        let resized = FakePlatform::with_monitors(vec![Rect::new(0, 0, 3840, 2160)]);
+        assert_eq!(
+            setting.get_phy_position(&resized, 200, 100).unwrap(),
+            Point::new(2900, 450)
+        );
+    }
+
+    #[test]
+    fn dragging_onto_another_monitor_pins_it() {
+        let mut setting = setting(WindowPosition::Center, MonitorSelector::PrimaryMonitor);
+        let platform = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 4480, 1440),
+        ]);
+
+        setting
+            .set_custom_position(&platform, Rect::new(2000, 100, 2200, 200))
+            .unwrap();
+
+        assert_eq!(
+            setting.monitor_selector,
+            MonitorSelector::Specific(platform.monitors()[1].id.clone())
+        );
+        assert_eq!(
+            setting.get_phy_position(&platform, 200, 100).unwrap(),
+            Point::new(2000, 100)
+        );
+    }
+
+    #[test]
+    fn dragging_on_all_monitors_keeps_mirroring() {
+        let mut setting = setting(WindowPosition::Center, MonitorSelector::AllMonitors);
+        let platform = FakePlatform::with_monitors(vec![
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(1920, 0, 4480, 1440),
+        ]);
+
+        // 拖动的是副显示器上的窗口
+        setting
+            .set_custom_position(&platform, Rect::new(2000, 100, 2200, 200))
+            .unwrap();
+
+        assert_eq!(setting.monitor_selector, MonitorSelector::AllMonitors);
+        assert_eq!(setting.position, WindowPosition::Custom);
+        assert_eq!(
+            setting.custom,
+            CustomPosition {
+                x: 180.0 / 2560.0,
+                y: 150.0 / 1440.0,
+            }
+        );
+    }
 }